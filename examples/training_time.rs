@@ -0,0 +1,53 @@
+//! Times the dense and sparse training functions on the 4000-review
+//! training set.
+//!
+//! Run with `cargo run --release --example training_time`.
+
+use automatic_review_analyzer::error::Result;
+use automatic_review_analyzer::features::{
+    bag_of_words, extract_bow_feature_vectors, extract_bow_sparse_feature_vectors,
+};
+use automatic_review_analyzer::reviews::{labels, load_reviews};
+use automatic_review_analyzer::sparse::{
+    average_perceptron_sparse, pegasos_sparse, perceptron_sparse,
+};
+use automatic_review_analyzer::{average_perceptron, pegasos, perceptron, DType};
+use std::time::Instant;
+
+fn time(name: &str, train: impl FnOnce() -> Result<(Vec<DType>, DType)>) -> Result<()> {
+    let start = Instant::now();
+    let (_, theta_0) = train()?;
+    println!("{name:<26} {:>8.1?}  theta_0 = {theta_0}", start.elapsed());
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let reviews = load_reviews("data/reviews_train.tsv")?;
+    let texts: Vec<&str> = reviews.iter().map(|review| review.text.as_str()).collect();
+    let labels = labels(&reviews)?;
+    let dictionary = bag_of_words(&texts, None);
+    let feature_matrix = extract_bow_feature_vectors(&texts, &dictionary);
+    let sparse_matrix = extract_bow_sparse_feature_vectors(&texts, &dictionary);
+    let t = 10;
+    println!(
+        "{} reviews, {} features, t = {t}",
+        feature_matrix.n_rows(),
+        feature_matrix.n_cols()
+    );
+
+    time("perceptron", || perceptron(&feature_matrix, &labels, t))?;
+    time("average_perceptron", || {
+        average_perceptron(&feature_matrix, &labels, t)
+    })?;
+    time("pegasos", || pegasos(&feature_matrix, &labels, t, 0.01))?;
+    time("perceptron_sparse", || {
+        perceptron_sparse(&sparse_matrix, &labels, t)
+    })?;
+    time("average_perceptron_sparse", || {
+        average_perceptron_sparse(&sparse_matrix, &labels, t)
+    })?;
+    time("pegasos_sparse", || {
+        pegasos_sparse(&sparse_matrix, &labels, t, 0.01)
+    })?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use automatic_review_analyzer::error::{Error, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+fn invalid(message: String) -> Error {
+    Error::InvalidParameter {
+        name: "arguments",
+        message,
+    }
+}
+
+/// Command-line options given as `--name value` pairs. Options are removed
+/// as they are read so [`Args::finish`] can reject the ones left over.
+pub struct Args {
+    values: HashMap<String, String>,
+}
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut values = HashMap::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| invalid(format!("expected an option, found {arg:?}")))?;
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("missing value for --{name}")))?;
+            if values.insert(name.to_string(), value).is_some() {
+                return Err(invalid(format!("--{name} given more than once")));
+            }
+        }
+        Ok(Self { values })
+    }
+
+    pub fn optional(&mut self, name: &str) -> Option<String> {
+        self.values.remove(name)
+    }
+
+    pub fn required(&mut self, name: &str) -> Result<String> {
+        self.optional(name)
+            .ok_or_else(|| invalid(format!("missing required option --{name}")))
+    }
+
+    /// Parses the value of `--name`, or returns `default` if it is absent.
+    pub fn value_or<T: FromStr>(&mut self, name: &str, default: T) -> Result<T> {
+        match self.optional(name) {
+            Some(value) => parse(name, &value),
+            None => Ok(default),
+        }
+    }
+
+    /// Parses a comma-separated list such as `--ts 1,5,10`.
+    pub fn list_or<T: FromStr>(&mut self, name: &str, default: &str) -> Result<Vec<T>> {
+        let value = self.optional(name).unwrap_or_else(|| default.to_string());
+        value
+            .split(',')
+            .map(|item| parse(name, item.trim()))
+            .collect()
+    }
+
+    /// Fails if an option was given that the command does not use.
+    pub fn finish(self) -> Result<()> {
+        let mut names: Vec<_> = self.values.into_keys().collect();
+        names.sort();
+        match names.first() {
+            Some(name) => Err(invalid(format!("unknown option --{name}"))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse<T: FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid(format!("invalid value {value:?} for --{name}")))
+}
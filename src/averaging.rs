@@ -0,0 +1,119 @@
+use crate::Float;
+
+/// Running average of the parameters after every step of an online
+/// algorithm, kept in O(1) per step and O(nnz) per update.
+///
+/// Instead of adding `theta` to a sum after each of the `n` steps, every
+/// change `delta` made at step `s` (counting from 1) is recorded as
+/// `s * delta`. With `u` the sum of these, the average of the iterates is
+/// `((n + 1) * theta - u) / n`. Sums are kept in `f64` because `s` grows
+/// to `n * T` and would quickly exhaust the precision of `f32`.
+#[derive(Debug, Clone)]
+pub(crate) struct LazyAverage {
+    pub(crate) weighted: Vec<f64>,
+    pub(crate) weighted_0: f64,
+    pub(crate) steps: usize,
+}
+
+impl LazyAverage {
+    pub(crate) fn new(n_feature: usize) -> Self {
+        Self {
+            weighted: vec![0.; n_feature],
+            weighted_0: 0.,
+            steps: 0,
+        }
+    }
+
+    /// Starts from non-zero parameters, as if `theta` and `theta_0` had been
+    /// added to zero parameters before the first step. Must be called before
+    /// any step.
+    pub(crate) fn start_from<F: Float>(&mut self, theta: &[F], theta_0: F) {
+        for (w, &x) in self.weighted.iter_mut().zip(theta) {
+            *w += x.to_f64();
+        }
+        self.weighted_0 += theta_0.to_f64();
+    }
+
+    /// Number of steps counted so far.
+    pub(crate) fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Counts a step, whether or not it changes the parameters.
+    pub(crate) fn step(&mut self) {
+        self.steps += 1;
+    }
+
+    /// Records that the current step added `coefficient * x` to `theta` and
+    /// `coefficient` to `theta_0`, given the non-zero `(index, value)`
+    /// entries of `x`.
+    pub(crate) fn record<F: Float>(
+        &mut self,
+        coefficient: F,
+        entries: impl IntoIterator<Item = (usize, F)>,
+    ) {
+        let weight = self.steps as f64 * coefficient.to_f64();
+        for (i, x) in entries {
+            self.weighted[i] += weight * x.to_f64();
+        }
+        self.weighted_0 += weight;
+    }
+
+    /// Returns the average of the iterates given the final parameters.
+    /// Must only be called after at least one step.
+    pub(crate) fn average<F: Float>(&self, theta: &[F], theta_0: F) -> (Vec<F>, F) {
+        let n = self.steps as f64;
+        let average =
+            |value: F, weighted: f64| F::from_f64(((n + 1.) * value.to_f64() - weighted) / n);
+        (
+            theta
+                .iter()
+                .zip(&self.weighted)
+                .map(|(&value, &weighted)| average(value, weighted))
+                .collect(),
+            average(theta_0, self.weighted_0),
+        )
+    }
+}
+
+/// Plain sum of the parameters after every step, for algorithms like
+/// Pegasos that change every parameter at every step anyway.
+#[derive(Debug, Clone)]
+pub(crate) struct RunningSum {
+    pub(crate) sum: Vec<f64>,
+    pub(crate) sum_0: f64,
+    pub(crate) count: usize,
+}
+
+impl RunningSum {
+    pub(crate) fn new(n_feature: usize) -> Self {
+        Self {
+            sum: vec![0.; n_feature],
+            sum_0: 0.,
+            count: 0,
+        }
+    }
+
+    /// Number of parameter values added so far.
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+
+    pub(crate) fn add<F: Float>(&mut self, theta: &[F], theta_0: F) {
+        for (s, &x) in self.sum.iter_mut().zip(theta) {
+            *s += x.to_f64();
+        }
+        self.sum_0 += theta_0.to_f64();
+        self.count += 1;
+    }
+
+    /// Returns the average of the added parameters. Must only be called
+    /// after at least one [`RunningSum::add`].
+    pub(crate) fn average<F: Float>(&self) -> (Vec<F>, F) {
+        let n = self.count as f64;
+        (
+            self.sum.iter().map(|&s| F::from_f64(s / n)).collect(),
+            F::from_f64(self.sum_0 / n),
+        )
+    }
+}
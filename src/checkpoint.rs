@@ -0,0 +1,338 @@
+use std::fs;
+
+use crate::error::{Error, Result};
+use crate::matrix::FeatureMatrix;
+use crate::model::{Algorithm, LinearModel};
+use crate::online::OnlineLearner;
+use crate::persist::{
+    counted_lines, expect_end, field, header, parse_error, parse_float, parse_value,
+};
+use crate::{check_dimension, check_order, check_training_data, DType, Float};
+
+/// First line of every checkpoint file, versioned like the model files of
+/// [`crate::persist`].
+const MAGIC: &str = "automatic_review_analyzer checkpoint";
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The complete state of a training run between two epochs: the settings,
+/// a fingerprint of the training data, the order the samples are visited
+/// in, the parameters, the step counter and the sums of the averaged
+/// algorithms.
+///
+/// Continuing from a checkpoint gives exactly the model an uninterrupted
+/// run of the corresponding training function would have returned, given
+/// the same order as [`crate::options::TrainOptions::order`].
+#[derive(Debug, Clone)]
+pub struct Checkpoint<F = DType> {
+    t: usize,
+    tail: Option<F>,
+    epoch: usize,
+    fingerprint: u64,
+    order: Vec<usize>,
+    learner: OnlineLearner<F>,
+}
+
+impl<F: Float> Checkpoint<F> {
+    /// Creates the state of a run that has not started yet.
+    ///
+    /// Args:
+    /// * `algorithm` - the algorithm to train with.
+    /// * `t` - number of iterations through the training data.
+    /// * `lambda` - the regularization parameter, ignored unless
+    ///   [`Algorithm::uses_lambda`].
+    /// * `tail` - fraction of the final steps averaged by
+    ///   [`Algorithm::AveragePegasos`], ignored by the other algorithms.
+    /// * `feature_matrix` - the training data, which every later call
+    ///   must be given again.
+    /// * `labels` - correct classification of each row, `1` or `-1`.
+    /// * `order` - the order to visit the samples in during every
+    ///   iteration, e.g. [`crate::order::get_order`] as the training
+    ///   functions use.
+    ///
+    /// Fails like the training function of the algorithm would for these
+    /// settings and data, or if `order` is not a permutation.
+    pub fn new(
+        algorithm: Algorithm,
+        t: usize,
+        lambda: F,
+        tail: F,
+        feature_matrix: &FeatureMatrix<F>,
+        labels: &[F],
+        order: Vec<usize>,
+    ) -> Result<Self> {
+        check_training_data(feature_matrix, labels)?;
+        check_order(&order, feature_matrix.n_rows())?;
+        let averaged = matches!(
+            algorithm,
+            Algorithm::AveragePerceptron | Algorithm::AveragePegasos
+        );
+        if averaged && t == 0 {
+            return Err(Error::InvalidParameter {
+                name: "t",
+                message: "the average over zero iterations is undefined".to_string(),
+            });
+        }
+        let mut learner = OnlineLearner::new(algorithm, feature_matrix.n_cols(), lambda)?;
+        if algorithm == Algorithm::AveragePegasos {
+            if !(tail > F::ZERO && tail <= F::ONE) {
+                return Err(Error::InvalidParameter {
+                    name: "tail",
+                    message: format!("{tail} is not in (0, 1]"),
+                });
+            }
+            let n_step = t * order.len();
+            let n_averaged = ((tail.to_f64() * n_step as f64).ceil() as usize).clamp(1, n_step);
+            learner.average_after = n_step - n_averaged;
+        }
+        Ok(Self {
+            t,
+            tail: algorithm.uses_tail().then_some(tail),
+            epoch: 0,
+            fingerprint: fingerprint(feature_matrix, labels),
+            order,
+            learner,
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.learner.algorithm()
+    }
+
+    /// Number of iterations through the training data.
+    pub fn t(&self) -> usize {
+        self.t
+    }
+
+    /// The regularization parameter, if the algorithm uses one.
+    pub fn lambda(&self) -> Option<F> {
+        self.algorithm()
+            .uses_lambda()
+            .then_some(self.learner.lambda)
+    }
+
+    /// Fraction of the final steps that are averaged, if the algorithm
+    /// uses one.
+    pub fn tail(&self) -> Option<F> {
+        self.tail
+    }
+
+    /// Number of iterations through the training data completed so far.
+    pub fn epoch(&self) -> usize {
+        self.epoch
+    }
+
+    /// Whether all `t` iterations have been completed.
+    pub fn is_finished(&self) -> bool {
+        self.epoch >= self.t
+    }
+
+    /// The model trained so far, see [`OnlineLearner::model`].
+    pub fn model(&self) -> LinearModel<F> {
+        self.learner.model()
+    }
+
+    /// Runs the next iteration through the training data.
+    ///
+    /// Fails if the data is invalid like for [`crate::perceptron`] or does
+    /// not have the shape the checkpoint was created for. Whether it is the
+    /// same data is only checked by [`train_with_checkpoints`], as that
+    /// takes another pass through it.
+    pub fn run_epoch(&mut self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()> {
+        self.check_data(feature_matrix, labels)?;
+        for &i in &self.order {
+            self.learner.step(feature_matrix.row(i), labels[i]);
+        }
+        self.epoch += 1;
+        Ok(())
+    }
+
+    fn check_data(&self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()> {
+        check_training_data(feature_matrix, labels)?;
+        check_dimension(
+            "number of samples",
+            self.order.len(),
+            feature_matrix.n_rows(),
+        )?;
+        check_dimension(
+            "number of features",
+            self.learner.theta.len(),
+            feature_matrix.n_cols(),
+        )
+    }
+
+    /// Formats the state as text. The first line is the format header,
+    /// followed by one tab-separated `name value` line per setting, one line
+    /// per index of the order and the state of the learner as
+    /// [`OnlineLearner::to_text`] writes it after its own header:
+    ///
+    /// ```text
+    /// automatic_review_analyzer checkpoint 1
+    /// t              10
+    /// tail
+    /// epoch          3
+    /// n_sample       2
+    /// data           9c1e0d6f4b2a7358
+    /// 1
+    /// 0
+    /// algorithm      average_perceptron
+    /// lambda
+    /// steps          6
+    /// average_after  0
+    /// averaged       6
+    /// theta_0        -1
+    /// sum_0          -4
+    /// n_feature      2
+    /// 0.5            3
+    /// -0.75          -4.5
+    /// ```
+    ///
+    /// `tail` is left empty for the algorithms that do not use it, and
+    /// `data` is the fingerprint of the training data in hexadecimal.
+    pub fn to_text(&self) -> String {
+        let tail = self.tail.map(|tail| tail.to_string()).unwrap_or_default();
+        let mut text = format!(
+            "{MAGIC} {FORMAT_VERSION}\nt\t{}\ntail\t{tail}\nepoch\t{}\nn_sample\t{}\ndata\t{:016x}\n",
+            self.t,
+            self.epoch,
+            self.order.len(),
+            self.fingerprint
+        );
+        for i in &self.order {
+            text.push_str(&format!("{i}\n"));
+        }
+        text.push_str(&self.learner.state_text());
+        text
+    }
+
+    /// Writes the text of [`Checkpoint::to_text`] to `path`. The text is
+    /// first written next to it and then moved over it, so a crash while
+    /// saving leaves the previous checkpoint intact.
+    pub fn save(&self, path: &str) -> Result<()> {
+        let temporary = format!("{path}.tmp");
+        fs::write(&temporary, self.to_text())?;
+        Ok(fs::rename(temporary, path)?)
+    }
+
+    /// Reads a checkpoint written by [`Checkpoint::save`].
+    pub fn load(path: &str) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the text of [`Checkpoint::to_text`].
+    ///
+    /// Fails with [`Error::Parse`] if the version is not [`FORMAT_VERSION`],
+    /// a field is missing or malformed, the order is not a permutation, or
+    /// the number of order or feature lines differs from `n_sample` or
+    /// `n_feature`.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let end = contents.lines().count() + 1;
+        let mut lines = contents.lines().enumerate().map(|(i, line)| (i + 1, line));
+        header(&mut lines, end, MAGIC, FORMAT_VERSION, "checkpoint")?;
+
+        let (line, t) = field(&mut lines, end, "t")?;
+        let t = parse_value::<usize>(line, "t", t)?;
+        let (tail_line, tail) = field(&mut lines, end, "tail")?;
+        let (line, epoch) = field(&mut lines, end, "epoch")?;
+        let epoch = parse_value::<usize>(line, "epoch", epoch)?;
+        let (line, n_sample) = field(&mut lines, end, "n_sample")?;
+        let n_sample = parse_value::<usize>(line, "n_sample", n_sample)?;
+        let (line, data) = field(&mut lines, end, "data")?;
+        let fingerprint = u64::from_str_radix(data, 16)
+            .map_err(|_| parse_error(line, format!("invalid data {data:?}")))?;
+
+        let order = counted_lines(&mut lines, end, n_sample, "order indices", |line, entry| {
+            parse_value::<usize>(line, "order", entry)
+        })?;
+        check_order(&order, n_sample).map_err(|err| parse_error(end, err.to_string()))?;
+
+        let learner = OnlineLearner::parse_state(&mut lines, end)?;
+        expect_end(&mut lines, "theta")?;
+        let tail = if learner.algorithm().uses_tail() {
+            Some(parse_float(tail_line, "tail", tail)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            t,
+            tail,
+            epoch,
+            fingerprint,
+            order,
+            learner,
+        })
+    }
+}
+
+/// Runs the remaining iterations of a training run, saving its state to
+/// `path` every `every` iterations and once more at the end.
+///
+/// Args:
+/// * `feature_matrix` - the training data the run was started with.
+/// * `labels` - correct classification of each row, `1` or `-1`.
+/// * `checkpoint` - the state to continue from, e.g. a fresh
+///   [`Checkpoint::new`].
+/// * `path` - file the state is saved to.
+/// * `every` - number of iterations between two saves.
+///
+/// Returns: the `(theta, theta_0)` tuple at the end of the run. Fails if
+///     `every` is zero, if the data is not the data the checkpoint was
+///     created for or if saving fails.
+pub fn train_with_checkpoints<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    mut checkpoint: Checkpoint<F>,
+    path: &str,
+    every: usize,
+) -> Result<(Vec<F>, F)> {
+    if every == 0 {
+        return Err(Error::InvalidParameter {
+            name: "every",
+            message: "checkpoints must be at least one iteration apart".to_string(),
+        });
+    }
+    checkpoint.check_data(feature_matrix, labels)?;
+    if fingerprint(feature_matrix, labels) != checkpoint.fingerprint {
+        return Err(Error::InvalidParameter {
+            name: "checkpoint",
+            message: "it was created for other training data".to_string(),
+        });
+    }
+    while !checkpoint.is_finished() {
+        checkpoint.run_epoch(feature_matrix, labels)?;
+        if checkpoint.epoch.is_multiple_of(every) || checkpoint.is_finished() {
+            checkpoint.save(path)?;
+        }
+    }
+    let LinearModel { theta, theta_0 } = checkpoint.model();
+    Ok((theta, theta_0))
+}
+
+/// Continues the training run saved at `path` like
+/// [`train_with_checkpoints`], which must be given the same training data.
+pub fn resume<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    path: &str,
+    every: usize,
+) -> Result<(Vec<F>, F)> {
+    let checkpoint = Checkpoint::load(path)?;
+    train_with_checkpoints(feature_matrix, labels, checkpoint, path, every)
+}
+
+/// Hashes the training data, so a run is not continued on other data of the
+/// same shape. This is FNV-1a over the bits of every value as an `f64`,
+/// which unlike [`std::hash::DefaultHasher`] gives the same hash in every
+/// build.
+fn fingerprint<F: Float>(feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let (n_rows, n_cols) = feature_matrix.shape();
+    let values = feature_matrix.as_slice().iter().chain(labels);
+    [n_rows as u64, n_cols as u64]
+        .into_iter()
+        .chain(values.map(|value| value.to_f64().to_bits()))
+        .flat_map(u64::to_le_bytes)
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
@@ -0,0 +1,203 @@
+use crate::error::{Error, Result};
+use crate::matrix::FeatureMatrix;
+use crate::model::{Algorithm, LinearModel};
+use crate::{average_pegasos, average_perceptron, pegasos, perceptron, DType, Float};
+
+/// A linear classifier that can be trained and then used for prediction,
+/// whatever the algorithm it trains with.
+pub trait Classifier<F: Float = DType> {
+    /// The algorithm the classifier trains with.
+    fn algorithm(&self) -> Algorithm;
+
+    /// Trains on the given data, replacing any earlier fit.
+    ///
+    /// Args:
+    /// * `feature_matrix` - matrix describing the given data. Each row
+    ///   represents a single data point.
+    /// * `labels` - correct classification of each row, `1` or `-1`.
+    ///
+    /// Fails like the training function of the algorithm, leaving the
+    /// classifier unfitted.
+    fn fit(&mut self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()>;
+
+    /// The trained model, or `None` before [`Classifier::fit`].
+    fn model(&self) -> Option<&LinearModel<F>>;
+
+    /// Computes `theta · x + theta_0` for every row of a feature matrix, see
+    /// [`LinearModel::decision_function_batch`]. Fails with
+    /// [`Error::NotFitted`] before [`Classifier::fit`].
+    fn decision_function(&self, feature_matrix: &FeatureMatrix<F>) -> Result<Vec<F>> {
+        self.model()
+            .ok_or(Error::NotFitted)?
+            .decision_function_batch(feature_matrix)
+    }
+
+    /// Classifies every row of a feature matrix, see
+    /// [`LinearModel::predict_batch`]. Fails with [`Error::NotFitted`] before
+    /// [`Classifier::fit`].
+    fn predict(&self, feature_matrix: &FeatureMatrix<F>) -> Result<Vec<F>> {
+        self.model()
+            .ok_or(Error::NotFitted)?
+            .predict_batch(feature_matrix)
+    }
+}
+
+/// Configuration of the [`perceptron`] algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perceptron<F = DType> {
+    /// Number of iterations through the training data.
+    pub t: usize,
+    model: Option<LinearModel<F>>,
+}
+
+impl<F: Float> Perceptron<F> {
+    pub fn new(t: usize) -> Self {
+        Self { t, model: None }
+    }
+}
+
+impl<F: Float> Classifier<F> for Perceptron<F> {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Perceptron
+    }
+
+    fn fit(&mut self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()> {
+        self.model = None;
+        self.model = Some(perceptron(feature_matrix, labels, self.t)?.into());
+        Ok(())
+    }
+
+    fn model(&self) -> Option<&LinearModel<F>> {
+        self.model.as_ref()
+    }
+}
+
+/// Configuration of the [`average_perceptron`] algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AveragePerceptron<F = DType> {
+    /// Number of iterations through the training data.
+    pub t: usize,
+    model: Option<LinearModel<F>>,
+}
+
+impl<F: Float> AveragePerceptron<F> {
+    pub fn new(t: usize) -> Self {
+        Self { t, model: None }
+    }
+}
+
+impl<F: Float> Classifier<F> for AveragePerceptron<F> {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::AveragePerceptron
+    }
+
+    fn fit(&mut self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()> {
+        self.model = None;
+        self.model = Some(average_perceptron(feature_matrix, labels, self.t)?.into());
+        Ok(())
+    }
+
+    fn model(&self) -> Option<&LinearModel<F>> {
+        self.model.as_ref()
+    }
+}
+
+/// Configuration of the [`pegasos`] algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pegasos<F = DType> {
+    /// Number of iterations through the training data.
+    pub t: usize,
+    /// The regularization parameter.
+    pub lambda: F,
+    model: Option<LinearModel<F>>,
+}
+
+impl<F: Float> Pegasos<F> {
+    pub fn new(t: usize, lambda: F) -> Self {
+        Self {
+            t,
+            lambda,
+            model: None,
+        }
+    }
+}
+
+impl<F: Float> Classifier<F> for Pegasos<F> {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Pegasos
+    }
+
+    fn fit(&mut self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()> {
+        self.model = None;
+        self.model = Some(pegasos(feature_matrix, labels, self.t, self.lambda)?.into());
+        Ok(())
+    }
+
+    fn model(&self) -> Option<&LinearModel<F>> {
+        self.model.as_ref()
+    }
+}
+
+/// Configuration of the [`average_pegasos`] algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AveragePegasos<F = DType> {
+    /// Number of iterations through the training data.
+    pub t: usize,
+    /// The regularization parameter.
+    pub lambda: F,
+    /// Fraction of the final steps whose iterates are averaged.
+    pub tail: F,
+    model: Option<LinearModel<F>>,
+}
+
+impl<F: Float> AveragePegasos<F> {
+    pub fn new(t: usize, lambda: F, tail: F) -> Self {
+        Self {
+            t,
+            lambda,
+            tail,
+            model: None,
+        }
+    }
+}
+
+impl<F: Float> Classifier<F> for AveragePegasos<F> {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::AveragePegasos
+    }
+
+    fn fit(&mut self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()> {
+        self.model = None;
+        let thetas = average_pegasos(feature_matrix, labels, self.t, self.lambda, self.tail)?;
+        self.model = Some(thetas.into());
+        Ok(())
+    }
+
+    fn model(&self) -> Option<&LinearModel<F>> {
+        self.model.as_ref()
+    }
+}
+
+/// Creates an unfitted classifier for `algorithm`, so the algorithm can be
+/// chosen at run time.
+///
+/// Args:
+/// * `algorithm` - the algorithm to train with.
+/// * `t` - number of iterations through the training data.
+/// * `lambda` - the regularization parameter, ignored unless
+///   [`Algorithm::uses_lambda`].
+/// * `tail` - fraction of the final steps averaged by
+///   [`Algorithm::AveragePegasos`], ignored by the other algorithms.
+pub fn for_algorithm<F: Float>(
+    algorithm: Algorithm,
+    t: usize,
+    lambda: F,
+    tail: F,
+) -> Box<dyn Classifier<F>> {
+    match algorithm {
+        Algorithm::Perceptron => Box::new(Perceptron::new(t)),
+        Algorithm::AveragePerceptron => Box::new(AveragePerceptron::new(t)),
+        Algorithm::Pegasos => Box::new(Pegasos::new(t, lambda)),
+        Algorithm::AveragePegasos => Box::new(AveragePegasos::new(t, lambda, tail)),
+    }
+}
@@ -0,0 +1,83 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use crate::matrix::ShapeError;
+
+/// Errors reported by the fallible functions of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// Input text is malformed at the given 1-based line.
+    Parse { line: usize, message: String },
+    /// Data does not have the shape of a matrix.
+    Shape(ShapeError),
+    /// Two inputs that must agree in size do not.
+    DimensionMismatch {
+        what: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// A dataset has no data points.
+    EmptyDataset,
+    /// The label of the given data point is neither `1` nor `-1`.
+    InvalidLabel { index: usize, value: f64 },
+    /// The given data point has no label.
+    MissingLabel { index: usize },
+    /// A feature value is NaN or infinite.
+    NonFiniteValue { row: usize, col: usize, value: f64 },
+    /// A hyperparameter is outside of its valid range.
+    InvalidParameter { name: &'static str, message: String },
+    /// A classifier was used before it was trained.
+    NotFitted,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "I/O error: {err}"),
+            Error::Parse { line, message } => write!(f, "line {line}: {message}"),
+            Error::Shape(err) => write!(f, "invalid shape: {err}"),
+            Error::DimensionMismatch {
+                what,
+                expected,
+                found,
+            } => write!(f, "{what}: expected {expected}, found {found}"),
+            Error::EmptyDataset => write!(f, "dataset is empty"),
+            Error::InvalidLabel { index, value } => {
+                write!(f, "label {index} is {value}, expected 1 or -1")
+            }
+            Error::MissingLabel { index } => write!(f, "data point {index} has no label"),
+            Error::NonFiniteValue { row, col, value } => {
+                write!(f, "feature ({row}, {col}) is {value}")
+            }
+            Error::InvalidParameter { name, message } => write!(f, "invalid {name}: {message}"),
+            Error::NotFitted => write!(f, "classifier has not been fitted"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Shape(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ShapeError> for Error {
+    fn from(err: ShapeError) -> Self {
+        Error::Shape(err)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::{Error, Result};
+use crate::matrix::FeatureMatrix;
+use crate::model::LinearModel;
+use crate::{check_dimension, check_labels, DType, Float};
+
+/// Computes the fraction of predictions that match the labels.
+///
+/// Args:
+/// * `preds` - array of predicted labels, `1` or `-1`.
+/// * `labels` - array of correct labels, in the same order as `preds`.
+///
+/// Returns: the accuracy as a number between 0 and 1. Fails if there are
+///     no predictions or if `preds` and `labels` differ in length.
+pub fn accuracy<F: Float>(preds: &[F], labels: &[F]) -> Result<F> {
+    check_dimension("number of labels", preds.len(), labels.len())?;
+    if preds.is_empty() {
+        return Err(Error::EmptyDataset);
+    }
+    let correct = preds
+        .iter()
+        .zip(labels.iter())
+        .filter(|(&pred, &label)| pred == label)
+        .count();
+    Ok(F::from_usize(correct) / F::from_usize(preds.len()))
+}
+
+/// Counts of correct and incorrect predictions for each class, where `1` is
+/// the positive class.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfusionMatrix {
+    pub true_positive: usize,
+    pub false_positive: usize,
+    pub true_negative: usize,
+    pub false_negative: usize,
+}
+
+impl ConfusionMatrix {
+    /// Tallies the predictions against the labels.
+    ///
+    /// Args:
+    /// * `preds` - array of predicted labels, `1` or `-1`.
+    /// * `labels` - array of correct labels, in the same order as `preds`.
+    ///
+    /// Returns: the confusion matrix. Fails if there are no predictions, if
+    ///     `preds` and `labels` differ in length or if a value is not `1` or
+    ///     `-1`.
+    pub fn from_predictions<F: Float>(preds: &[F], labels: &[F]) -> Result<Self> {
+        check_dimension("number of labels", preds.len(), labels.len())?;
+        if preds.is_empty() {
+            return Err(Error::EmptyDataset);
+        }
+        check_labels(preds)?;
+        check_labels(labels)?;
+        let mut matrix = Self::default();
+        for (&pred, &label) in preds.iter().zip(labels.iter()) {
+            match (pred > F::ZERO, label > F::ZERO) {
+                (true, true) => matrix.true_positive += 1,
+                (true, false) => matrix.false_positive += 1,
+                (false, false) => matrix.true_negative += 1,
+                (false, true) => matrix.false_negative += 1,
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Number of predictions.
+    pub fn total(&self) -> usize {
+        self.true_positive + self.false_positive + self.true_negative + self.false_negative
+    }
+
+    /// Fraction of predictions that are correct, see [`accuracy`].
+    pub fn accuracy(&self) -> DType {
+        ratio(self.true_positive + self.true_negative, self.total())
+    }
+
+    /// Fraction of positive predictions that are correct, or `0` if nothing
+    /// was predicted positive.
+    pub fn precision(&self) -> DType {
+        ratio(self.true_positive, self.true_positive + self.false_positive)
+    }
+
+    /// Fraction of positive data points that were predicted positive, or `0`
+    /// if there are none.
+    pub fn recall(&self) -> DType {
+        ratio(self.true_positive, self.true_positive + self.false_negative)
+    }
+
+    /// Harmonic mean of precision and recall, or `0` if both are zero.
+    pub fn f1(&self) -> DType {
+        ratio(
+            2 * self.true_positive,
+            2 * self.true_positive + self.false_positive + self.false_negative,
+        )
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> DType {
+    if denominator == 0 {
+        0 as DType
+    } else {
+        numerator as DType / denominator as DType
+    }
+}
+
+/// Trains a classifier on the training data and reports its accuracy on
+/// both the training and the validation data.
+///
+/// Args:
+/// * `classifier` - a training function given the training feature matrix
+///   and labels, e.g. `|m, l| pegasos(m, l, t, lambda)`.
+/// * `train_feature_matrix` - matrix of the training data. Each row
+///   represents a single data point.
+/// * `val_feature_matrix` - matrix of the validation data, with the same
+///   number of columns as the training data.
+/// * `train_labels` - correct classification of each training data point.
+/// * `val_labels` - correct classification of each validation data point.
+///
+/// Returns a tuple containing two values:
+/// * the accuracy of the trained classifier on the training data
+/// * the accuracy of the trained classifier on the validation data
+///
+/// Fails if training fails or if the validation data does not match the
+/// trained model.
+pub fn classifier_accuracy<F, C>(
+    classifier: C,
+    train_feature_matrix: &FeatureMatrix<F>,
+    val_feature_matrix: &FeatureMatrix<F>,
+    train_labels: &[F],
+    val_labels: &[F],
+) -> Result<(F, F)>
+where
+    F: Float,
+    C: FnOnce(&FeatureMatrix<F>, &[F]) -> Result<(Vec<F>, F)>,
+{
+    let model = LinearModel::from(classifier(train_feature_matrix, train_labels)?);
+    let train_accuracy = accuracy(&model.predict_batch(train_feature_matrix)?, train_labels)?;
+    let val_accuracy = accuracy(&model.predict_batch(val_feature_matrix)?, val_labels)?;
+    Ok((train_accuracy, val_accuracy))
+}
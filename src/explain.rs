@@ -0,0 +1,58 @@
+use std::cmp::Ordering;
+
+use crate::error::Result;
+use crate::features::Dictionary;
+use crate::{check_dimension, DType, Float};
+
+/// A word together with its coefficient in `theta`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordWeight<F = DType> {
+    pub word: String,
+    pub weight: F,
+}
+
+/// The words that push predictions furthest towards each class.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Explanation<F = DType> {
+    /// Words with positive coefficients, largest first.
+    pub positive: Vec<WordWeight<F>>,
+    /// Words with negative coefficients, most negative first.
+    pub negative: Vec<WordWeight<F>>,
+}
+
+/// Finds the words with the largest positive and the most negative
+/// coefficients of a bag-of-words model.
+///
+/// Args:
+/// * `theta` - the feature-coefficient parameter of a trained model.
+/// * `dictionary` - the dictionary the model was trained against, with one
+///   word per coefficient.
+/// * `k` - the maximum number of words returned for each class.
+///
+/// Returns: up to `k` words of each sign. Words with a zero coefficient are
+///     never returned and ties keep dictionary order. Fails if the dictionary
+///     does not have one word per coefficient.
+pub fn most_explanatory_words<F: Float>(
+    theta: &[F],
+    dictionary: &Dictionary,
+    k: usize,
+) -> Result<Explanation<F>> {
+    check_dimension("vocabulary size", theta.len(), dictionary.len())?;
+
+    let word_weight = |i: usize| WordWeight {
+        word: dictionary.words()[i].clone(),
+        weight: theta[i],
+    };
+
+    // Only non-zero and therefore comparable coefficients are sorted.
+    let compare = |a: F, b: F| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    let mut positive: Vec<usize> = (0..theta.len()).filter(|&i| theta[i] > F::ZERO).collect();
+    positive.sort_by(|&i, &j| compare(theta[j], theta[i]));
+    let positive = positive.into_iter().take(k).map(word_weight).collect();
+
+    let mut negative: Vec<usize> = (0..theta.len()).filter(|&i| theta[i] < F::ZERO).collect();
+    negative.sort_by(|&i, &j| compare(theta[i], theta[j]));
+    let negative = negative.into_iter().take(k).map(word_weight).collect();
+
+    Ok(Explanation { positive, negative })
+}
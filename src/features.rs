@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use crate::error::Result;
+use crate::matrix::FeatureMatrix;
+use crate::sparse::SparseMatrix;
+use crate::DType;
+
+const PUNCTUATION: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+/// A bag-of-words dictionary mapping every known word to its feature index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    indices_by_word: HashMap<String, usize>,
+    words: Vec<String>,
+}
+
+impl Dictionary {
+    /// Creates a dictionary from words listed in feature index order.
+    /// Repeated words keep their first index.
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut dictionary = Self::default();
+        for word in words {
+            dictionary.insert(word.into());
+        }
+        dictionary
+    }
+
+    /// Number of words, i.e. the dimension `d` of the feature vectors.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the feature index of `word`, if it is in the dictionary.
+    pub fn index(&self, word: &str) -> Option<usize> {
+        self.indices_by_word.get(word).copied()
+    }
+
+    /// Returns the word assigned to feature `index`.
+    pub fn word(&self, index: usize) -> Option<&str> {
+        self.words.get(index).map(String::as_str)
+    }
+
+    /// All words, in feature index order.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    fn insert(&mut self, word: String) {
+        if !self.indices_by_word.contains_key(&word) {
+            self.indices_by_word.insert(word.clone(), self.words.len());
+            self.words.push(word);
+        }
+    }
+}
+
+/// A set of words that are never given a feature index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stopwords {
+    words: HashSet<String>,
+}
+
+impl Stopwords {
+    /// Loads a stopword list with one word per line, such as
+    /// `data/stopwords.txt`. Blank lines are skipped.
+    pub fn load(path: &str) -> Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Parses a stopword list with one word per line, see [`Stopwords::load`].
+    pub fn parse(contents: &str) -> Self {
+        Self::from_words(contents.lines().map(str::trim).filter(|w| !w.is_empty()))
+    }
+
+    /// Creates a stopword list from the given words, lowercased to match the
+    /// output of [`extract_words`].
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            words: words
+                .into_iter()
+                .map(|w| w.as_ref().to_lowercase())
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+}
+
+/// Splits a text into lowercase words.
+/// Punctuation and digits are separated from the surrounding characters so
+/// that each of them becomes a word on its own.
+///
+/// Args:
+/// * `text` - the text of a review.
+///
+/// Returns: the words of `text`, in order of appearance.
+pub fn extract_words(text: &str) -> Vec<String> {
+    let mut spaced = String::with_capacity(text.len());
+    for c in text.chars() {
+        if PUNCTUATION.contains(c) || c.is_ascii_digit() {
+            spaced.push(' ');
+            spaced.push(c);
+            spaced.push(' ');
+        } else {
+            spaced.push(c);
+        }
+    }
+    spaced
+        .to_lowercase()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Builds the unigram dictionary of a set of texts.
+/// Words are indexed in order of first appearance.
+///
+/// Args:
+/// * `texts` - the texts of the training reviews.
+/// * `stopwords` - words to leave out of the dictionary, or `None` to keep
+///   every word.
+///
+/// Returns: a dictionary assigning a feature index to every distinct word.
+pub fn bag_of_words<S: AsRef<str>>(texts: &[S], stopwords: Option<&Stopwords>) -> Dictionary {
+    let mut dictionary = Dictionary::default();
+    for text in texts {
+        for word in extract_words(text.as_ref()) {
+            if stopwords.is_some_and(|stopwords| stopwords.contains(&word)) {
+                continue;
+            }
+            dictionary.insert(word);
+        }
+    }
+    dictionary
+}
+
+/// Computes the bag-of-words feature vector of every text.
+/// The ith coordinate of a feature vector is `1` if the ith word of the
+/// dictionary appears in the text and `0` otherwise; words missing from the
+/// dictionary are ignored.
+///
+/// Args:
+/// * `texts` - the texts of the reviews to featurize.
+/// * `dictionary` - the dictionary built by [`bag_of_words`].
+///
+/// Returns: a feature matrix with one row of length `dictionary.len()` per text.
+pub fn extract_bow_feature_vectors<S: AsRef<str>>(
+    texts: &[S],
+    dictionary: &Dictionary,
+) -> FeatureMatrix {
+    let mut feature_matrix = FeatureMatrix::zeros(texts.len(), dictionary.len());
+    for (i, text) in texts.iter().enumerate() {
+        let feature_vector = feature_matrix.row_mut(i);
+        for word in extract_words(text.as_ref()) {
+            if let Some(index) = dictionary.index(&word) {
+                feature_vector[index] = 1 as DType;
+            }
+        }
+    }
+    feature_matrix
+}
+
+/// Computes the bag-of-words feature vectors like
+/// [`extract_bow_feature_vectors`], storing only the words that appear.
+pub fn extract_bow_sparse_feature_vectors<S: AsRef<str>>(
+    texts: &[S],
+    dictionary: &Dictionary,
+) -> SparseMatrix {
+    let mut feature_matrix = SparseMatrix::new(dictionary.len());
+    for text in texts {
+        let mut indices = extract_words(text.as_ref())
+            .iter()
+            .filter_map(|word| dictionary.index(word))
+            .collect::<Vec<usize>>();
+        indices.sort_unstable();
+        indices.dedup();
+        let values = vec![1 as DType; indices.len()];
+        feature_matrix
+            .push_row(&indices, &values)
+            .expect("dictionary indices are below the dictionary length");
+    }
+    feature_matrix
+}
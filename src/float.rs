@@ -0,0 +1,90 @@
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Floating point types the loss, update and training functions, and the
+/// file formats, can work with. Implemented for `f32`, the default
+/// [`crate::DType`], and `f64`.
+pub trait Float:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + FromStr
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    /// Tolerance under which a value is treated as zero, e.g. a decision
+    /// function that counts as a mistake.
+    const TOLERANCE: Self;
+
+    /// Converts a count, e.g. a number of updates, rounding if needed.
+    fn from_usize(n: usize) -> Self;
+    /// Converts an `f64`, rounding if needed.
+    fn from_f64(x: f64) -> Self;
+    /// Converts to `f64`, which is lossless for both implementations.
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($t:ty) => {
+        impl Float for $t {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+            const TOLERANCE: Self = 1e-7;
+
+            fn from_usize(n: usize) -> Self {
+                n as $t
+            }
+
+            fn from_f64(x: f64) -> Self {
+                x as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                <$t>::max(self, other)
+            }
+
+            fn min(self, other: Self) -> Self {
+                <$t>::min(self, other)
+            }
+
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
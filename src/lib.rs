@@ -1,5 +1,130 @@
+mod averaging;
+pub mod checkpoint;
+pub mod classifier;
+pub mod error;
+pub mod evaluation;
+pub mod explain;
+pub mod features;
+pub mod float;
+pub mod matrix;
+pub mod model;
+pub mod observer;
+pub mod online;
+pub mod options;
+pub mod order;
+pub mod persist;
+pub mod plot;
+pub mod reviews;
+pub mod sparse;
+pub mod stopping;
+pub mod tuning;
+
+use averaging::{LazyAverage, RunningSum};
+use error::{Error, Result};
+pub use float::Float;
+use matrix::FeatureMatrix;
+use model::LinearModel;
+use observer::{EpochInfo, StepInfo};
+use options::TrainOptions;
+use stopping::{EarlyStopping, EarlyStoppingResult, StoppingRule};
+
 pub type DType = f32;
 
+/// Computes the dot product of two arrays of the same length.
+pub fn dot<F: Float>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b.iter()).map(|(&a, &b)| a * b).sum::<F>()
+}
+
+/// Checks that `found` matches the `expected` size of `what`.
+pub(crate) fn check_dimension(what: &'static str, expected: usize, found: usize) -> Result<()> {
+    if expected != found {
+        return Err(Error::DimensionMismatch {
+            what,
+            expected,
+            found,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that every label is `1` or `-1`.
+pub(crate) fn check_labels<F: Float>(labels: &[F]) -> Result<()> {
+    match labels
+        .iter()
+        .position(|&label| label != F::ONE && label != -F::ONE)
+    {
+        Some(index) => Err(Error::InvalidLabel {
+            index,
+            value: labels[index].to_f64(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks that a training set is non-empty, has one ±1 label per row and
+/// only finite feature values.
+pub(crate) fn check_training_data<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+) -> Result<()> {
+    if feature_matrix.is_empty() {
+        return Err(Error::EmptyDataset);
+    }
+    check_dimension("number of labels", feature_matrix.n_rows(), labels.len())?;
+    check_labels(labels)?;
+    for (row, feature_vector) in feature_matrix.rows().enumerate() {
+        if let Some(col) = feature_vector.iter().position(|x| !x.is_finite()) {
+            return Err(Error::NonFiniteValue {
+                row,
+                col,
+                value: feature_vector[col].to_f64(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the Pegasos regularization parameter is finite and non-negative.
+pub(crate) fn check_lambda<F: Float>(lambda: F) -> Result<()> {
+    if !(lambda.is_finite() && lambda >= F::ZERO) {
+        return Err(Error::InvalidParameter {
+            name: "lambda",
+            message: format!("{lambda} is not a finite non-negative number"),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `order` is a permutation of `0..n_samples`.
+pub(crate) fn check_order(order: &[usize], n_samples: usize) -> Result<()> {
+    check_dimension("length of the order", n_samples, order.len())?;
+    let mut seen = vec![false; n_samples];
+    for &i in order {
+        if i >= n_samples || seen[i] {
+            return Err(Error::InvalidParameter {
+                name: "order",
+                message: format!("index {i} makes the order not a permutation"),
+            });
+        }
+        seen[i] = true;
+    }
+    Ok(())
+}
+
+/// Checks that the `initial` model of a warm start is finite and has
+/// `n_feature` coefficients.
+pub(crate) fn check_initial<F: Float>(initial: &LinearModel<F>, n_feature: usize) -> Result<()> {
+    check_dimension("length of theta", n_feature, initial.n_feature())?;
+    let mut values = initial.theta.iter().chain([&initial.theta_0]);
+    if let Some(&value) = values.find(|value| !value.is_finite()) {
+        return Err(Error::InvalidParameter {
+            name: "initial",
+            message: format!("{value} is not a finite parameter"),
+        });
+    }
+    Ok(())
+}
+
 /// Finds the hinge loss on a single data point given specific classification
 /// parameters.
 ///
@@ -11,20 +136,10 @@ pub type DType = f32;
 ///
 /// Returns: the hinge loss, as a float, associated with the given data point and
 ///     parameters.
-pub fn hinge_loss_single(
-    feature_vector: &[DType],
-    label: DType,
-    theta: &[DType],
-    theta_0: DType,
-) -> DType {
-    let output = feature_vector
-        .iter()
-        .zip(theta.iter())
-        .map(|(&a, &b)| a * b)
-        .sum::<DType>()
-        + theta_0;
-    let one = 1 as DType;
-    let zero = 0 as DType;
+pub fn hinge_loss_single<F: Float>(feature_vector: &[F], label: F, theta: &[F], theta_0: F) -> F {
+    let output = dot(feature_vector, theta) + theta_0;
+    let one = F::ONE;
+    let zero = F::ZERO;
 
     (one - output * label).max(zero)
 }
@@ -34,24 +149,50 @@ pub fn hinge_loss_single(
 /// Args:
 /// - `feature_matrix` - matrix describing the given data. Each row represents a single data point.
 /// - `labels` - array where the kth element of the array is the correct classification of
-///     the kth row of the feature matrix.
+///   the kth row of the feature matrix.
 /// - `theta` - array describing the linear classifier.
 /// - `theta_0` - real valued number representing the offset parameter.
 ///
 /// Returns: the hinge loss, as a float, associated with the given dataset and parameters.
 ///     This number should be the average hinge loss across all of
-pub fn hinge_loss_full(
-    feature_matrix: &Vec<Vec<DType>>,
-    labels: &[DType],
-    theta: &[DType],
-    theta_0: DType,
-) -> DType {
-    feature_matrix
-        .iter()
+///     the data points. Fails if the dataset is empty or the sizes of
+///     `labels` and `theta` do not match the feature matrix.
+pub fn hinge_loss_full<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    theta: &[F],
+    theta_0: F,
+) -> Result<F> {
+    if feature_matrix.is_empty() {
+        return Err(Error::EmptyDataset);
+    }
+    check_dimension("number of labels", feature_matrix.n_rows(), labels.len())?;
+    check_dimension("length of theta", feature_matrix.n_cols(), theta.len())?;
+    Ok(feature_matrix
+        .rows()
         .zip(labels.iter())
         .map(|(feature_vector, &label)| hinge_loss_single(feature_vector, label, theta, theta_0))
-        .sum::<DType>()
-        / DType::from(labels.len() as DType)
+        .sum::<F>()
+        / F::from_usize(labels.len()))
+}
+
+/// Classifies every data point of a feature matrix with the given parameters.
+///
+/// Args:
+/// * `feature_matrix` - matrix describing the given data. Each row
+///   represents a single data point.
+/// * `theta` - array describing the linear classifier.
+/// * `theta_0` - real valued number representing the offset parameter.
+///
+/// Returns: an array where the kth element is `1` if the kth row is
+///     classified as positive and `-1` otherwise, see [`LinearModel::predict`].
+///     Fails if the length of `theta` does not match the feature matrix.
+pub fn classify<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    theta: &[F],
+    theta_0: F,
+) -> Result<Vec<F>> {
+    LinearModel::new(theta.to_vec(), theta_0).predict_batch(feature_matrix)
 }
 
 /// Updates the classification parameters `theta` and `theta_0` via a single
@@ -64,44 +205,55 @@ pub fn hinge_loss_full(
 /// * `current_theta` - The current theta being used by the perceptron
 ///   algorithm before this update.
 /// * `current_theta_0` - The current theta_0 being used by the perceptron
-///    algorithm before this update.
+///   algorithm before this update.
 ///
 /// Returns a tuple containing two values:
 /// * the updated feature-coefficient parameter `theta` as a numpy array
 /// * the updated offset parameter `theta_0` as a floating point number
-pub fn perceptron_single_step_update(
-    feature_vector: &[DType],
-    label: DType,
-    theta: &[DType],
-    theta_0: DType,
-) -> (Vec<DType>, DType) {
-    let output = theta
-        .iter()
-        .zip(feature_vector.iter())
-        .map(|(&a, &b)| a * b)
-        .sum::<DType>()
-        + theta_0;
-
-    if label * output <= 1e-7 {
-        let new_theta = theta
-            .iter()
-            .zip(
-                feature_vector
-                    .iter()
-                    .map(|&x| x * label)
-                    .collect::<Vec<DType>>()
-                    .iter(),
-            )
-            .map(|(&a, &b)| a + b)
-            .collect();
-        (new_theta, theta_0 + label)
-    } else {
-        (theta.to_vec(), theta_0)
+pub fn perceptron_single_step_update<F: Float>(
+    feature_vector: &[F],
+    label: F,
+    theta: &[F],
+    theta_0: F,
+) -> (Vec<F>, F) {
+    let mut new_theta = theta.to_vec();
+    let mut new_theta_0 = theta_0;
+    perceptron_single_step_update_in_place(feature_vector, label, &mut new_theta, &mut new_theta_0);
+    (new_theta, new_theta_0)
+}
+
+/// Updates `theta` and `theta_0` in place via a single step of the
+/// perceptron algorithm, without allocating.
+///
+/// Args:
+/// * `feature_vector` - array describing a single data point.
+/// * `label` - the correct classification of the feature vector.
+/// * `theta` - the current theta, updated in place.
+/// * `theta_0` - the current theta_0, updated in place.
+///
+/// Returns: whether the data point was misclassified and the parameters
+///     updated, see [`perceptron_single_step_update`].
+pub fn perceptron_single_step_update_in_place<F: Float>(
+    feature_vector: &[F],
+    label: F,
+    theta: &mut [F],
+    theta_0: &mut F,
+) -> bool {
+    let output = dot(theta, feature_vector) + *theta_0;
+    if label * output > F::TOLERANCE {
+        return false;
     }
+    for (t, &x) in theta.iter_mut().zip(feature_vector) {
+        *t += x * label;
+    }
+    *theta_0 += label;
+    true
 }
 
 /// Runs the full perceptron algorithm on a given set of data.
-/// Runs t iterations through the data set: we do not stop early.
+/// Runs t iterations through the data set: we do not stop early, see
+/// [`perceptron_early_stopping`] for that.
+/// Samples are visited in the order given by [`order::get_order`].
 ///
 /// Args:
 /// * `feature_matrix` - matrix describing the given data. Each row
@@ -116,33 +268,121 @@ pub fn perceptron_single_step_update(
 ///   (found after T iterations through the feature matrix)
 /// * the offset parameter `theta_0` as a floating point number
 ///   (found also after T iterations through the feature matrix).
-pub fn perceptron(
-    feature_matrix: &Vec<Vec<DType>>,
-    labels: &[DType],
+///
+/// Fails if the feature matrix is empty or contains NaN or infinite values,
+/// or if `labels` is not one `1` or `-1` per row.
+pub fn perceptron<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    t: usize,
+) -> Result<(Vec<F>, F)> {
+    perceptron_with_options(feature_matrix, labels, t, TrainOptions::default())
+}
+
+/// Runs the perceptron algorithm like [`perceptron`], with the order,
+/// observer and initial parameters given by `options`. Training ends early,
+/// with the parameters reached so far, as soon as the observer breaks.
+///
+/// Fails like [`perceptron`], or if the options are invalid, see
+/// [`TrainOptions`].
+pub fn perceptron_with_options<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
     t: usize,
-) -> (Vec<DType>, DType) {
-    let n_sample = feature_matrix.len();
-    let n_feature = feature_matrix[0].len();
-
-    let mut theta = vec![0 as DType; n_feature];
-    let mut theta_0 = 0 as DType;
-
-    for _ in 0..t {
-        for i in 0..n_sample {
-            let feature_vector = &feature_matrix[i];
-            let label = labels[i];
-            (theta, theta_0) = perceptron_single_step_update(feature_vector, label, &theta, theta_0)
+    options: TrainOptions<'_, F>,
+) -> Result<(Vec<F>, F)> {
+    check_training_data(feature_matrix, labels)?;
+    let order = options.order_for(feature_matrix.n_rows())?;
+    let LinearModel {
+        mut theta,
+        mut theta_0,
+    } = options.initial_for(feature_matrix.n_cols())?;
+    let mut no_observer = ();
+    let observer = options.observer.unwrap_or(&mut no_observer);
+    let mut step = 0;
+
+    'epochs: for epoch in 1..=t {
+        let mut mistakes = 0;
+        for &i in order.iter() {
+            step += 1;
+            let mistake = perceptron_single_step_update_in_place(
+                feature_matrix.row(i),
+                labels[i],
+                &mut theta,
+                &mut theta_0,
+            );
+            mistakes += usize::from(mistake);
+            let info = StepInfo {
+                epoch,
+                step,
+                sample: i,
+                mistake,
+                theta: &theta,
+                theta_0,
+            };
+            if observer.on_step(&info).is_break() {
+                break 'epochs;
+            }
+        }
+        let info = EpochInfo {
+            epoch,
+            step,
+            mistakes,
+            theta: &theta,
+            theta_0,
+        };
+        if observer.on_epoch(&info).is_break() {
+            break;
         }
     }
-    (theta, theta_0)
+    Ok((theta, theta_0))
+}
+
+/// Runs the perceptron algorithm like [`perceptron`] for at most `t`
+/// iterations, stopping earlier as the `rule` says.
+///
+/// Args:
+/// * `rule` - when to stop before `t` iterations, see [`StoppingRule`].
+///   With [`StoppingRule::Fixed`] this trains exactly like [`perceptron`].
+///
+/// Returns: the parameters kept, the epoch they were reached at and the
+///     number of epochs run, see [`EarlyStoppingResult`].
+///
+/// Fails on invalid data like [`perceptron`], or if the validation data of
+/// [`StoppingRule::Patience`] is invalid, does not have the same number of
+/// features, or the patience is zero.
+pub fn perceptron_early_stopping<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    t: usize,
+    rule: StoppingRule<'_, F>,
+) -> Result<EarlyStoppingResult<F>> {
+    rule.check(feature_matrix.n_cols())?;
+    let mut observer = EarlyStopping::new(rule);
+    let options = TrainOptions {
+        observer: Some(&mut observer),
+        ..TrainOptions::default()
+    };
+    let thetas = perceptron_with_options(feature_matrix, labels, t, options)?;
+    let epochs_run = observer.epochs();
+    let (thetas, best_epoch) = match observer.into_best()? {
+        Some((best, epoch)) => ((best.theta, best.theta_0), epoch),
+        None => (thetas, epochs_run),
+    };
+    Ok(EarlyStoppingResult {
+        thetas,
+        best_epoch,
+        epochs_run,
+    })
 }
 
 /// Runs the average perceptron algorithm on a given dataset.
 /// Runs `t` iterations through the dataset (we do not stop early) and
 /// therefore averages over `t` many parameter values.
+/// Samples are visited in the order given by [`order::get_order`].
 ///
-/// NOTE: It is more difficult to keep a running average than to sum and
-/// divide.
+/// The average is kept lazily, so a step only costs more than the update
+/// itself when the data point is misclassified.
 ///
 /// Args:
 /// * `feature_matrix` - A matrix describing the given data. Each row
@@ -157,40 +397,96 @@ pub fn perceptron(
 ///   (averaged over T iterations through the feature matrix)
 /// * the average offset parameter `theta_0` as a floating point number
 ///   (averaged also over T iterations through the feature matrix).
-pub fn average_perceptron(
-    feature_matrix: &Vec<Vec<DType>>,
-    labels: &[DType],
+///
+/// Fails on invalid data like [`perceptron`], or if `t` is zero.
+pub fn average_perceptron<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
     t: usize,
-) -> (Vec<DType>, DType) {
-    let n_sample = feature_matrix.len();
-    let n_feature = feature_matrix[0].len();
-
-    let mut theta = vec![0 as DType; n_feature];
-    let mut theta_sum = vec![0 as DType; n_feature];
-    let mut theta_0 = 0 as DType;
-    let mut theta_0_sum = 0 as DType;
-
-    for _ in 0..t {
-        for i in 0..n_sample {
-            let feature_vector = &feature_matrix[i];
-            let label = labels[i];
-            (theta, theta_0) =
-                perceptron_single_step_update(feature_vector, label, &theta, theta_0);
-            theta_sum = theta_sum
-                .iter()
-                .zip(theta.iter())
-                .map(|(&a, &b)| a + b)
-                .collect::<Vec<DType>>();
-            theta_0_sum += theta_0;
-        }
+) -> Result<(Vec<F>, F)> {
+    average_perceptron_with_options(feature_matrix, labels, t, TrainOptions::default())
+}
+
+/// Runs the average perceptron algorithm like [`average_perceptron`], with
+/// the order, observer and initial parameters given by `options`. If the
+/// observer ends training early, the average is taken over the steps made
+/// so far. The average is over the iterates that follow the initial
+/// parameters, not including them.
+///
+/// Fails like [`average_perceptron`], or if the options are invalid, see
+/// [`TrainOptions`].
+pub fn average_perceptron_with_options<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    t: usize,
+    options: TrainOptions<'_, F>,
+) -> Result<(Vec<F>, F)> {
+    check_training_data(feature_matrix, labels)?;
+    let order = options.order_for(feature_matrix.n_rows())?;
+    let initial = options.initial_for(feature_matrix.n_cols())?;
+    if t == 0 {
+        return Err(Error::InvalidParameter {
+            name: "t",
+            message: "the average over zero iterations is undefined".to_string(),
+        });
     }
+    let mut no_observer = ();
+    let observer = options.observer.unwrap_or(&mut no_observer);
+    let mut average = LazyAverage::new(feature_matrix.n_cols());
+    average.start_from(&initial.theta, initial.theta_0);
+    let LinearModel {
+        mut theta,
+        mut theta_0,
+    } = initial;
+    let mut step = 0;
 
-    let all_iter = (t * n_sample) as DType;
-    let new_theta = theta_sum
-        .iter()
-        .map(|&a| a / all_iter)
-        .collect::<Vec<DType>>();
-    (new_theta, theta_0_sum / all_iter)
+    'epochs: for epoch in 1..=t {
+        let mut mistakes = 0;
+        for &i in order.iter() {
+            step += 1;
+            let feature_vector = feature_matrix.row(i);
+            average.step();
+            let mistake = perceptron_single_step_update_in_place(
+                feature_vector,
+                labels[i],
+                &mut theta,
+                &mut theta_0,
+            );
+            if mistake {
+                mistakes += 1;
+                average.record(
+                    labels[i],
+                    feature_vector
+                        .iter()
+                        .copied()
+                        .enumerate()
+                        .filter(|&(_, x)| x != F::ZERO),
+                );
+            }
+            let info = StepInfo {
+                epoch,
+                step,
+                sample: i,
+                mistake,
+                theta: &theta,
+                theta_0,
+            };
+            if observer.on_step(&info).is_break() {
+                break 'epochs;
+            }
+        }
+        let info = EpochInfo {
+            epoch,
+            step,
+            mistakes,
+            theta: &theta,
+            theta_0,
+        };
+        if observer.on_epoch(&info).is_break() {
+            break;
+        }
+    }
+    Ok(average.average(&theta, theta_0))
 }
 
 /// Updates the classification parameters `theta` and `theta_0`
@@ -209,39 +505,64 @@ pub fn average_perceptron(
 /// a tuple where the first element is an array with the value of theta after
 /// the old update has completed and the second element is a real valued number with
 ///  the value of `theta_0` after the old updated has completed.
-pub fn pegasos_single_step_update(
-    feature_vector: &[DType],
-    label: DType,
-    lambda: DType,
-    eta: DType,
-    theta: &[DType],
-    theta_0: DType,
-) -> (Vec<DType>, DType) {
-    let margin_factor = label
-        * (feature_vector
-            .iter()
-            .zip(theta.iter())
-            .map(|(&a, &b)| a * b)
-            .sum::<DType>()
-            + theta_0);
-    let one = 1 as DType;
-    let zero = 0 as DType;
-    let is_violation = if margin_factor <= one { one } else { zero };
-
-    (
-        theta
-            .iter()
-            .zip(feature_vector.iter())
-            .map(|(&t, &f)| t + eta * (is_violation * label * f - lambda * t))
-            .collect::<Vec<DType>>(),
-        theta_0 + eta * (is_violation * label),
-    )
+pub fn pegasos_single_step_update<F: Float>(
+    feature_vector: &[F],
+    label: F,
+    lambda: F,
+    eta: F,
+    theta: &[F],
+    theta_0: F,
+) -> (Vec<F>, F) {
+    let mut new_theta = theta.to_vec();
+    let mut new_theta_0 = theta_0;
+    pegasos_single_step_update_in_place(
+        feature_vector,
+        label,
+        lambda,
+        eta,
+        &mut new_theta,
+        &mut new_theta_0,
+    );
+    (new_theta, new_theta_0)
+}
+
+/// Updates `theta` and `theta_0` in place via a single step of the Pegasos
+/// algorithm, without allocating.
+///
+/// Args:
+/// * `feature_vector` - array describing a single data point.
+/// * `label` - the correct classification of the feature vector.
+/// * `lambda` - the lambda value being used to update the parameters.
+/// * `eta` - learning rate to update parameters.
+/// * `theta` - the current theta, updated in place.
+/// * `theta_0` - the current theta_0, updated in place.
+///
+/// Returns: whether the margin was violated and the data point added to
+///     theta, see [`pegasos_single_step_update`].
+pub fn pegasos_single_step_update_in_place<F: Float>(
+    feature_vector: &[F],
+    label: F,
+    lambda: F,
+    eta: F,
+    theta: &mut [F],
+    theta_0: &mut F,
+) -> bool {
+    let margin_factor = label * (dot(feature_vector, theta) + *theta_0);
+    let is_violation = margin_factor <= F::ONE;
+    let violation = if is_violation { F::ONE } else { F::ZERO };
+
+    for (t, &f) in theta.iter_mut().zip(feature_vector) {
+        *t += eta * (violation * label * f - lambda * *t);
+    }
+    *theta_0 += eta * (violation * label);
+    is_violation
 }
 
 /// Runs the Pegasos algorithm on a given set of data.
 /// Runs T iterations through the data set, there is no need to worry about stopping early.
 /// For each update, set learning rate = 1/sqrt(t), where t is a counter for the
 /// number of updates performed so far (between 1 and nT inclusive).
+/// Samples are visited in the order given by [`order::get_order`].
 ///
 /// Args:
 /// * `feature_matrix` - A matrix describing the given data. Each row represents a single data point.
@@ -256,28 +577,206 @@ pub fn pegasos_single_step_update(
 /// the linear classification parameter, found after T iterations through the feature matrix and
 /// the second element is a real number with the value of the theta_0,
 /// the offset classification parameter, found after T iterations through the feature matrix.
-pub fn pegasos(
-    feature_matrix: &Vec<Vec<DType>>,
-    labels: &[DType],
+///
+/// Fails on invalid data like [`perceptron`], or if `lambda` is negative,
+/// NaN or infinite.
+pub fn pegasos<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
     t: usize,
-    lambda: DType,
-) -> (Vec<DType>, DType) {
-    let n_sample = feature_matrix.len();
-    let n_feature = feature_matrix[0].len();
-    let mut theta = vec![0 as DType; n_feature];
-    let mut theta_0 = 0 as DType;
+    lambda: F,
+) -> Result<(Vec<F>, F)> {
+    pegasos_with_options(feature_matrix, labels, t, lambda, TrainOptions::default())
+}
+
+/// Runs the Pegasos algorithm like [`pegasos`], with the order, observer
+/// and initial parameters given by `options`. Training ends early, with the
+/// parameters reached so far, as soon as the observer breaks.
+///
+/// Fails like [`pegasos`], or if the options are invalid, see
+/// [`TrainOptions`].
+pub fn pegasos_with_options<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    t: usize,
+    lambda: F,
+    options: TrainOptions<'_, F>,
+) -> Result<(Vec<F>, F)> {
+    check_training_data(feature_matrix, labels)?;
+    let order = options.order_for(feature_matrix.n_rows())?;
+    check_lambda(lambda)?;
+    let LinearModel {
+        mut theta,
+        mut theta_0,
+    } = options.initial_for(feature_matrix.n_cols())?;
+    let mut no_observer = ();
+    let observer = options.observer.unwrap_or(&mut no_observer);
     let mut count = 0;
 
-    for _ in 0..t {
-        for i in 0..n_sample {
+    'epochs: for epoch in 1..=t {
+        let mut mistakes = 0;
+        for &i in order.iter() {
             count += 1;
-            let eta = 1 as DType / DType::sqrt(count as f32);
-            let feature_vector = &feature_matrix[i];
-            let label = labels[i];
-            (theta, theta_0) =
-                pegasos_single_step_update(feature_vector, label, lambda, eta, &theta, theta_0);
+            let eta = F::ONE / F::from_usize(count).sqrt();
+            let mistake = pegasos_single_step_update_in_place(
+                feature_matrix.row(i),
+                labels[i],
+                lambda,
+                eta,
+                &mut theta,
+                &mut theta_0,
+            );
+            mistakes += usize::from(mistake);
+            let info = StepInfo {
+                epoch,
+                step: count,
+                sample: i,
+                mistake,
+                theta: &theta,
+                theta_0,
+            };
+            if observer.on_step(&info).is_break() {
+                break 'epochs;
+            }
+        }
+        let info = EpochInfo {
+            epoch,
+            step: count,
+            mistakes,
+            theta: &theta,
+            theta_0,
+        };
+        if observer.on_epoch(&info).is_break() {
+            break;
         }
     }
 
-    (theta, theta_0)
+    Ok((theta, theta_0))
+}
+
+/// Runs the averaged (Polyak-Ruppert) Pegasos algorithm on a given set of
+/// data. Training is the same as [`pegasos`], but the returned parameters
+/// are the average of the iterates after each update instead of the last
+/// iterate, which smooths out the oscillation of the slowly decaying
+/// learning rate. Samples are visited in the order given by [`order::get_order`].
+///
+/// Args:
+/// * `feature_matrix` - A matrix describing the given data. Each row represents a single data point.
+/// * `labels` - An array where the kth element of the array is the correct classification of
+///   the kth row of the feature matrix.
+/// * `t` - An integer indicating how many times the algorithm should iterate through
+///   the feature matrix.
+/// * `lambda` - The lambda value being used to update the Pegasos algorithm parameters.
+/// * `tail` - The fraction of the nT steps, counted from the end, whose
+///   iterates are averaged. `1` averages over all steps; smaller values
+///   leave out the early iterates that are still far from the optimum.
+///
+/// Returns: the tuple `(theta, theta_0)` of averaged parameters.
+///
+/// Fails on invalid data or `lambda` like [`pegasos`], if `t` is zero or
+/// if `tail` is not in `(0, 1]`.
+pub fn average_pegasos<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    t: usize,
+    lambda: F,
+    tail: F,
+) -> Result<(Vec<F>, F)> {
+    average_pegasos_with_options(
+        feature_matrix,
+        labels,
+        t,
+        lambda,
+        tail,
+        TrainOptions::default(),
+    )
+}
+
+/// Runs the averaged Pegasos algorithm like [`average_pegasos`], with the
+/// order, observer and initial parameters given by `options`. If the
+/// observer ends training early, the average is taken over the part of the
+/// tail reached so far, or is the latest iterate if training stopped before
+/// the tail.
+///
+/// Fails like [`average_pegasos`], or if the options are invalid, see
+/// [`TrainOptions`].
+pub fn average_pegasos_with_options<F: Float>(
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    t: usize,
+    lambda: F,
+    tail: F,
+    options: TrainOptions<'_, F>,
+) -> Result<(Vec<F>, F)> {
+    check_training_data(feature_matrix, labels)?;
+    let order = options.order_for(feature_matrix.n_rows())?;
+    check_lambda(lambda)?;
+    let LinearModel {
+        mut theta,
+        mut theta_0,
+    } = options.initial_for(feature_matrix.n_cols())?;
+    if t == 0 {
+        return Err(Error::InvalidParameter {
+            name: "t",
+            message: "the average over zero iterations is undefined".to_string(),
+        });
+    }
+    if !(tail > F::ZERO && tail <= F::ONE) {
+        return Err(Error::InvalidParameter {
+            name: "tail",
+            message: format!("{tail} is not in (0, 1]"),
+        });
+    }
+    let mut no_observer = ();
+    let observer = options.observer.unwrap_or(&mut no_observer);
+    let n_step = t * order.len();
+    let n_averaged = ((tail.to_f64() * n_step as f64).ceil() as usize).clamp(1, n_step);
+    let mut sum = RunningSum::new(feature_matrix.n_cols());
+    let mut count = 0;
+
+    'epochs: for epoch in 1..=t {
+        let mut mistakes = 0;
+        for &i in order.iter() {
+            count += 1;
+            let eta = F::ONE / F::from_usize(count).sqrt();
+            let mistake = pegasos_single_step_update_in_place(
+                feature_matrix.row(i),
+                labels[i],
+                lambda,
+                eta,
+                &mut theta,
+                &mut theta_0,
+            );
+            mistakes += usize::from(mistake);
+            if count > n_step - n_averaged {
+                sum.add(&theta, theta_0);
+            }
+            let info = StepInfo {
+                epoch,
+                step: count,
+                sample: i,
+                mistake,
+                theta: &theta,
+                theta_0,
+            };
+            if observer.on_step(&info).is_break() {
+                break 'epochs;
+            }
+        }
+        let info = EpochInfo {
+            epoch,
+            step: count,
+            mistakes,
+            theta: &theta,
+            theta_0,
+        };
+        if observer.on_epoch(&info).is_break() {
+            break;
+        }
+    }
+
+    if sum.count() == 0 {
+        return Ok((theta, theta_0));
+    }
+    Ok(sum.average())
 }
@@ -1,23 +1,413 @@
-use automatic_review_analyzer::{average_perceptron, pegasos, perceptron, DType};
+use automatic_review_analyzer::checkpoint::{train_with_checkpoints, Checkpoint};
+use automatic_review_analyzer::classifier::{
+    for_algorithm, AveragePerceptron, Classifier, Pegasos, Perceptron,
+};
+use automatic_review_analyzer::error::{Error, Result};
+use automatic_review_analyzer::evaluation::{accuracy, ConfusionMatrix};
+use automatic_review_analyzer::explain::most_explanatory_words;
+use automatic_review_analyzer::features::{bag_of_words, extract_bow_feature_vectors, Stopwords};
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::{Algorithm, LinearModel};
+use automatic_review_analyzer::order::get_order;
+use automatic_review_analyzer::persist::SavedModel;
+use automatic_review_analyzer::plot::save_toy_plot;
+use automatic_review_analyzer::reviews::{labels, load_reviews, write_submission, Review};
+use automatic_review_analyzer::tuning::{self, TuningGrid};
+use automatic_review_analyzer::DType;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::args::Args;
 use crate::utils::load_toy_data;
 
+mod args;
 mod utils;
 
-fn main() {
-    let (toy_features, toy_labels) = load_toy_data("data/toy_data.tsv");
+const USAGE: &str = "\
+usage: automatic_review_analyzer <command> [--option value]...
+
+commands:
+  train     --input <reviews.tsv> --output <model> [--algorithm pegasos]
+            [--t 10] [--lambda 0.01] [--tail 1] [--stopwords <file>]
+            [--checkpoint <file> | --resume <file>] [--checkpoint-every 1]
+  predict   --model <model> --input <reviews.tsv> [--output <labels>]
+  evaluate  --model <model> --input <reviews.tsv>
+  explain   --model <model> [--k 10]
+  submit    --model <model> --output <reviews.tsv>
+            [--input data/reviews_submit.tsv]
+  tune      --train <reviews.tsv> --val <reviews.tsv> [--algorithm pegasos]
+            [--ts 1,5,10,15,25,50] [--lambdas 0.001,0.01,0.1,1,10] [--tail 1]
+            [--stopwords <file>] [--output <report.tsv>]
+  toy       [--input data/toy_data.tsv] [--plot-dir <dir>]
+            (default when no command is given)
+  help
+
+algorithms: perceptron, average_perceptron, pegasos, average_pegasos
+--lambda is used by the Pegasos variants and --tail, the fraction of final
+steps whose iterates are averaged, by average_pegasos only.
+--checkpoint saves the training state to a new <file> every
+--checkpoint-every iterations. --resume continues the run saved in <file>,
+which must have the same settings and training data, and keeps saving to it.";
+
+const DEFAULT_T: usize = 10;
+const DEFAULT_LAMBDA: DType = 0.01;
+const DEFAULT_TAIL: DType = 1.;
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let command = args.next().unwrap_or_else(|| "toy".to_string());
+    let result = Args::parse(args).and_then(|args| match command.as_str() {
+        "train" => train(args),
+        "predict" => predict(args),
+        "evaluate" => evaluate(args),
+        "explain" => explain(args),
+        "submit" => submit(args),
+        "tune" => tune(args),
+        "toy" => toy(args),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(Error::InvalidParameter {
+            name: "command",
+            message: format!("unknown command {command:?}"),
+        }),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            if let Error::InvalidParameter { .. } = err {
+                eprintln!("\n{USAGE}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reads `--algorithm`, then `--lambda` and `--tail` for the algorithms
+/// that use them.
+fn algorithm_args(args: &mut Args) -> Result<(Algorithm, Option<DType>, Option<DType>)> {
+    let algorithm = match args.optional("algorithm") {
+        Some(name) => name.parse::<Algorithm>()?,
+        None => Algorithm::Pegasos,
+    };
+    let lambda = if algorithm.uses_lambda() {
+        Some(args.value_or("lambda", DEFAULT_LAMBDA)?)
+    } else {
+        None
+    };
+    let tail = tail_arg(args, algorithm)?;
+    Ok((algorithm, lambda, tail))
+}
+
+fn tail_arg(args: &mut Args, algorithm: Algorithm) -> Result<Option<DType>> {
+    if algorithm.uses_tail() {
+        Ok(Some(args.value_or("tail", DEFAULT_TAIL)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn stopwords_arg(args: &mut Args) -> Result<Option<Stopwords>> {
+    args.optional("stopwords")
+        .map(|path| Stopwords::load(&path))
+        .transpose()
+}
+
+fn review_texts(reviews: &[Review]) -> Vec<&str> {
+    reviews.iter().map(|review| review.text.as_str()).collect()
+}
+
+/// Loads a saved model and the features of the reviews in `path` under its
+/// vocabulary.
+fn load_model_and_reviews(
+    model_path: &str,
+    path: &str,
+) -> Result<(SavedModel, Vec<Review>, FeatureMatrix)> {
+    let saved = SavedModel::load(model_path)?;
+    let vocabulary = saved
+        .vocabulary
+        .as_ref()
+        .ok_or_else(|| Error::InvalidParameter {
+            name: "model",
+            message: format!("{model_path} has no vocabulary to extract features with"),
+        })?;
+    let reviews = load_reviews(path)?;
+    let feature_matrix = extract_bow_feature_vectors(&review_texts(&reviews), vocabulary);
+    Ok((saved, reviews, feature_matrix))
+}
+
+fn train(mut args: Args) -> Result<()> {
+    let input = args.required("input")?;
+    let output = args.required("output")?;
+    let (algorithm, lambda, tail) = algorithm_args(&mut args)?;
+    let t = args.value_or("t", DEFAULT_T)?;
+    let stopwords = stopwords_arg(&mut args)?;
+    let checkpoint_path = args.optional("checkpoint");
+    let resume_path = args.optional("resume");
+    let every = args.value_or("checkpoint-every", 1)?;
+    args.finish()?;
+
+    let reviews = load_reviews(&input)?;
+    let texts = review_texts(&reviews);
+    let labels = labels(&reviews)?;
+    let dictionary = bag_of_words(&texts, stopwords.as_ref());
+    let feature_matrix = extract_bow_feature_vectors(&texts, &dictionary);
+    let checkpoint = match (checkpoint_path, resume_path) {
+        (Some(_), Some(_)) => {
+            return Err(Error::InvalidParameter {
+                name: "checkpoint",
+                message: "give either --checkpoint or --resume, not both".to_string(),
+            })
+        }
+        (Some(path), None) => {
+            if Path::new(&path).exists() {
+                return Err(Error::InvalidParameter {
+                    name: "checkpoint",
+                    message: format!(
+                        "{path} already exists, pass --resume {path} to continue that run"
+                    ),
+                });
+            }
+            let checkpoint = Checkpoint::new(
+                algorithm,
+                t,
+                lambda.unwrap_or(DEFAULT_LAMBDA),
+                tail.unwrap_or(DEFAULT_TAIL),
+                &feature_matrix,
+                &labels,
+                get_order(feature_matrix.n_rows()),
+            )?;
+            Some((checkpoint, path))
+        }
+        (None, Some(path)) => {
+            let checkpoint = Checkpoint::load(&path)?;
+            let settings = (
+                checkpoint.algorithm(),
+                checkpoint.t(),
+                checkpoint.lambda(),
+                checkpoint.tail(),
+            );
+            if settings != (algorithm, t, lambda, tail) {
+                return Err(Error::InvalidParameter {
+                    name: "checkpoint",
+                    message: format!("{path} was saved by a run with other settings"),
+                });
+            }
+            println!(
+                "resuming from {path} after {} of {t} iterations",
+                checkpoint.epoch()
+            );
+            Some((checkpoint, path))
+        }
+        (None, None) => None,
+    };
+    let model = match checkpoint {
+        Some((checkpoint, path)) => LinearModel::from(train_with_checkpoints(
+            &feature_matrix,
+            &labels,
+            checkpoint,
+            &path,
+            every,
+        )?),
+        None => {
+            let mut classifier = for_algorithm(
+                algorithm,
+                t,
+                lambda.unwrap_or(DEFAULT_LAMBDA),
+                tail.unwrap_or(DEFAULT_TAIL),
+            );
+            classifier.fit(&feature_matrix, &labels)?;
+            classifier.model().cloned().ok_or(Error::NotFitted)?
+        }
+    };
+    let train_accuracy = accuracy(&model.predict_batch(&feature_matrix)?, &labels)?;
+
+    SavedModel::new(algorithm, t, lambda, tail, model, Some(dictionary))?.save(&output)?;
+    println!(
+        "trained {algorithm} on {} reviews ({} words), training accuracy {train_accuracy:.4}",
+        reviews.len(),
+        feature_matrix.n_cols()
+    );
+    println!("model written to {output}");
+    Ok(())
+}
+
+fn predict(mut args: Args) -> Result<()> {
+    let model_path = args.required("model")?;
+    let input = args.required("input")?;
+    let output = args.optional("output");
+    args.finish()?;
+
+    let (saved, _, feature_matrix) = load_model_and_reviews(&model_path, &input)?;
+    let preds = saved.model.predict_batch(&feature_matrix)?;
+    let text: String = preds.iter().map(|pred| format!("{pred}\n")).collect();
+    match output {
+        Some(path) => fs::write(path, text)?,
+        None => io::stdout().write_all(text.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn evaluate(mut args: Args) -> Result<()> {
+    let model_path = args.required("model")?;
+    let input = args.required("input")?;
+    args.finish()?;
+
+    let (saved, reviews, feature_matrix) = load_model_and_reviews(&model_path, &input)?;
+    let preds = saved.model.predict_batch(&feature_matrix)?;
+    let matrix = ConfusionMatrix::from_predictions(&preds, &labels(&reviews)?)?;
+    println!("reviews    {}", matrix.total());
+    println!("accuracy   {:.4}", matrix.accuracy());
+    println!("precision  {:.4}", matrix.precision());
+    println!("recall     {:.4}", matrix.recall());
+    println!("f1         {:.4}", matrix.f1());
+    println!();
+    println!("           predicted 1  predicted -1");
+    println!(
+        "actual 1   {:>11}  {:>12}",
+        matrix.true_positive, matrix.false_negative
+    );
+    println!(
+        "actual -1  {:>11}  {:>12}",
+        matrix.false_positive, matrix.true_negative
+    );
+    Ok(())
+}
+
+fn explain(mut args: Args) -> Result<()> {
+    let model_path = args.required("model")?;
+    let k = args.value_or("k", 10)?;
+    args.finish()?;
+
+    let saved: SavedModel = SavedModel::load(&model_path)?;
+    let vocabulary = saved
+        .vocabulary
+        .as_ref()
+        .ok_or_else(|| Error::InvalidParameter {
+            name: "model",
+            message: format!("{model_path} has no vocabulary to explain"),
+        })?;
+    let explanation = most_explanatory_words(&saved.model.theta, vocabulary, k)?;
+    for (title, words) in [
+        ("positive", &explanation.positive),
+        ("negative", &explanation.negative),
+    ] {
+        println!("most {title} words:");
+        for word_weight in words {
+            println!("  {:>10.4}  {}", word_weight.weight, word_weight.word);
+        }
+    }
+    Ok(())
+}
+
+fn submit(mut args: Args) -> Result<()> {
+    let model_path = args.required("model")?;
+    let output = args.required("output")?;
+    let input = args
+        .optional("input")
+        .unwrap_or_else(|| "data/reviews_submit.tsv".to_string());
+    args.finish()?;
+
+    let (saved, reviews, feature_matrix) = load_model_and_reviews(&model_path, &input)?;
+    let preds = saved.model.predict_batch(&feature_matrix)?;
+    write_submission(&input, &output, &preds)?;
+    println!("{} predictions written to {output}", reviews.len());
+    Ok(())
+}
+
+fn tune(mut args: Args) -> Result<()> {
+    let train_path = args.required("train")?;
+    let val_path = args.required("val")?;
+    let algorithm = match args.optional("algorithm") {
+        Some(name) => name.parse::<Algorithm>()?,
+        None => Algorithm::Pegasos,
+    };
+    let ts = args.list_or::<usize>("ts", "1,5,10,15,25,50")?;
+    let lambdas = if algorithm.uses_lambda() {
+        args.list_or::<DType>("lambdas", "0.001,0.01,0.1,1,10")?
+    } else {
+        Vec::new()
+    };
+    let tail = tail_arg(&mut args, algorithm)?;
+    let stopwords = stopwords_arg(&mut args)?;
+    let output = args.optional("output");
+    args.finish()?;
+
+    let train_reviews = load_reviews(&train_path)?;
+    let val_reviews = load_reviews(&val_path)?;
+    let train_texts = review_texts(&train_reviews);
+    let dictionary = bag_of_words(&train_texts, stopwords.as_ref());
+    let train_feature_matrix = extract_bow_feature_vectors(&train_texts, &dictionary);
+    let val_feature_matrix = extract_bow_feature_vectors(&review_texts(&val_reviews), &dictionary);
+    let train_labels = labels(&train_reviews)?;
+    let val_labels = labels(&val_reviews)?;
+
+    let grid = TuningGrid {
+        algorithm,
+        ts,
+        lambdas,
+        tail: tail.unwrap_or(DEFAULT_TAIL),
+    };
+    let report = tuning::tune(
+        &grid,
+        &train_feature_matrix,
+        &val_feature_matrix,
+        &train_labels,
+        &val_labels,
+    )?;
+    print!("{}", report.to_tsv());
+    if let Some(best) = report.best() {
+        let mut setting = format!("t={}", best.t);
+        if let Some(lambda) = best.lambda {
+            setting.push_str(&format!(" lambda={lambda}"));
+        }
+        if let Some(tail) = best.tail {
+            setting.push_str(&format!(" tail={tail}"));
+        }
+        println!("best: {setting} val_accuracy={:.4}", best.val_accuracy);
+    }
+    if let Some(path) = output {
+        report.write_tsv(&path)?;
+    }
+    Ok(())
+}
+
+fn toy(mut args: Args) -> Result<()> {
+    let input = args
+        .optional("input")
+        .unwrap_or_else(|| "data/toy_data.tsv".to_string());
+    let plot_dir = args.optional("plot-dir");
+    args.finish()?;
+
+    let (toy_features, toy_labels) = load_toy_data(&input)?;
     let iteration = 10;
     let lambda = 0.2;
-    let thetas_perceptron = perceptron(&toy_features, &toy_labels, iteration);
-    let thetas_avg_perceptron = average_perceptron(&toy_features, &toy_labels, iteration);
-    let thetas_pegasos = pegasos(&toy_features, &toy_labels, iteration, lambda);
-
-    fn plot_toy_results(algo_name: &str, thetas: (Vec<DType>, DType)) {
-        println!("theta for {algo_name} is {:?}", thetas.0);
-        println!("theta_0 for {algo_name} is {}", thetas.1);
-        // plot_toy_data(algo_name, toy_features, toy_labels, thetas)
-    }
+    let classifiers: [(&str, Box<dyn Classifier>); 3] = [
+        ("Perceptron", Box::new(Perceptron::new(iteration))),
+        (
+            "Average Perceptron",
+            Box::new(AveragePerceptron::new(iteration)),
+        ),
+        ("Pegasos", Box::new(Pegasos::new(iteration, lambda))),
+    ];
 
-    plot_toy_results("Perceptron", thetas_perceptron);
-    plot_toy_results("Average Perceptron", thetas_avg_perceptron);
-    plot_toy_results("Pegasos", thetas_pegasos);
+    for (algo_name, mut classifier) in classifiers {
+        classifier.fit(&toy_features, &toy_labels)?;
+        let model = classifier.model().ok_or(Error::NotFitted)?;
+        println!("theta for {algo_name} is {:?}", model.theta);
+        println!("theta_0 for {algo_name} is {}", model.theta_0);
+        if let Some(dir) = &plot_dir {
+            let path = Path::new(dir).join(format!("toy_{}.svg", classifier.algorithm()));
+            let path = path.to_string_lossy();
+            let thetas = (model.theta.clone(), model.theta_0);
+            save_toy_plot(&path, algo_name, &toy_features, &toy_labels, &thetas)?;
+            println!("plot written to {path}");
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,159 @@
+use std::error;
+use std::fmt;
+
+use crate::{DType, Float};
+
+/// Error returned when data does not have the shape of a matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeError {
+    /// The flat data does not hold `n_rows * n_cols` values.
+    DataLength { expected: usize, found: usize },
+    /// A row does not have as many values as the matrix has columns.
+    RowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A sparse index is not below the number of columns.
+    IndexOutOfBounds { index: usize, n_cols: usize },
+    /// Sparse indices are not strictly increasing.
+    UnsortedIndices { position: usize },
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::DataLength { expected, found } => {
+                write!(f, "expected {expected} values, found {found}")
+            }
+            ShapeError::RowLength {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row} has {found} values, expected {expected}"),
+            ShapeError::IndexOutOfBounds { index, n_cols } => {
+                write!(f, "index {index} out of bounds for {n_cols} columns")
+            }
+            ShapeError::UnsortedIndices { position } => {
+                write!(
+                    f,
+                    "indices are not strictly increasing at position {position}"
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for ShapeError {}
+
+/// A dense feature matrix stored contiguously in row-major order.
+/// Each row represents a single data point and all rows have the same length.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeatureMatrix<F = DType> {
+    data: Vec<F>,
+    n_rows: usize,
+    n_cols: usize,
+}
+
+impl<F: Float> FeatureMatrix<F> {
+    /// Creates a matrix from its values in row-major order.
+    pub fn new(data: Vec<F>, n_rows: usize, n_cols: usize) -> Result<Self, ShapeError> {
+        if data.len() != n_rows * n_cols {
+            return Err(ShapeError::DataLength {
+                expected: n_rows * n_cols,
+                found: data.len(),
+            });
+        }
+        Ok(Self {
+            data,
+            n_rows,
+            n_cols,
+        })
+    }
+
+    /// Creates a matrix filled with zeros.
+    pub fn zeros(n_rows: usize, n_cols: usize) -> Self {
+        Self {
+            data: vec![F::ZERO; n_rows * n_cols],
+            n_rows,
+            n_cols,
+        }
+    }
+
+    /// Creates a matrix from its rows, which must all have the same length.
+    pub fn from_rows<R: AsRef<[F]>>(rows: &[R]) -> Result<Self, ShapeError> {
+        let n_cols = rows.first().map_or(0, |row| row.as_ref().len());
+        let mut matrix = Self {
+            data: Vec::with_capacity(rows.len() * n_cols),
+            n_rows: 0,
+            n_cols,
+        };
+        for row in rows {
+            matrix.push_row(row.as_ref())?;
+        }
+        Ok(matrix)
+    }
+
+    /// Appends a row, which must have [`FeatureMatrix::n_cols`] values.
+    pub fn push_row(&mut self, row: &[F]) -> Result<(), ShapeError> {
+        if row.len() != self.n_cols {
+            return Err(ShapeError::RowLength {
+                row: self.n_rows,
+                expected: self.n_cols,
+                found: row.len(),
+            });
+        }
+        self.data.extend_from_slice(row);
+        self.n_rows += 1;
+        Ok(())
+    }
+
+    /// Returns `(n_rows, n_cols)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.n_rows, self.n_cols)
+    }
+
+    /// Number of data points.
+    pub fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    /// Number of features of each data point.
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n_rows == 0
+    }
+
+    /// Returns the feature vector of the ith data point.
+    pub fn row(&self, i: usize) -> &[F] {
+        assert!(i < self.n_rows, "row {i} out of bounds");
+        &self.data[i * self.n_cols..(i + 1) * self.n_cols]
+    }
+
+    /// Returns the feature vector of the ith data point for modification.
+    pub fn row_mut(&mut self, i: usize) -> &mut [F] {
+        assert!(i < self.n_rows, "row {i} out of bounds");
+        &mut self.data[i * self.n_cols..(i + 1) * self.n_cols]
+    }
+
+    /// Iterates over the rows in order.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[F]> + '_ {
+        (0..self.n_rows).map(move |i| self.row(i))
+    }
+
+    /// All values in row-major order.
+    pub fn as_slice(&self) -> &[F] {
+        &self.data
+    }
+}
+
+impl<F: Float> TryFrom<Vec<Vec<F>>> for FeatureMatrix<F> {
+    type Error = ShapeError;
+
+    fn try_from(rows: Vec<Vec<F>>) -> Result<Self, Self::Error> {
+        Self::from_rows(&rows)
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::matrix::FeatureMatrix;
+use crate::{check_dimension, dot, DType, Float};
+
+/// A trained linear classifier `theta · x + theta_0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearModel<F = DType> {
+    /// The feature-coefficient parameter.
+    pub theta: Vec<F>,
+    /// The offset parameter.
+    pub theta_0: F,
+}
+
+impl<F: Float> LinearModel<F> {
+    pub fn new(theta: Vec<F>, theta_0: F) -> Self {
+        Self { theta, theta_0 }
+    }
+
+    /// Creates a model whose parameters are all zero.
+    pub fn zeros(n_feature: usize) -> Self {
+        Self::new(vec![F::ZERO; n_feature], F::ZERO)
+    }
+
+    /// Number of features the model expects.
+    pub fn n_feature(&self) -> usize {
+        self.theta.len()
+    }
+
+    /// Computes `theta · x + theta_0` for a single data point.
+    pub fn decision_function(&self, feature_vector: &[F]) -> F {
+        dot(&self.theta, feature_vector) + self.theta_0
+    }
+
+    /// Classifies a single data point.
+    ///
+    /// Returns: `1` if the decision function is greater than [`Float::TOLERANCE`] and
+    ///     `-1` otherwise, so points on the decision boundary are negative.
+    pub fn predict(&self, feature_vector: &[F]) -> F {
+        if self.decision_function(feature_vector) > F::TOLERANCE {
+            F::ONE
+        } else {
+            -F::ONE
+        }
+    }
+
+    /// Computes the decision function of every row of a feature matrix.
+    /// Fails if the matrix does not have [`LinearModel::n_feature`] columns.
+    pub fn decision_function_batch(&self, feature_matrix: &FeatureMatrix<F>) -> Result<Vec<F>> {
+        check_dimension(
+            "number of features",
+            self.n_feature(),
+            feature_matrix.n_cols(),
+        )?;
+        Ok(feature_matrix
+            .rows()
+            .map(|feature_vector| self.decision_function(feature_vector))
+            .collect())
+    }
+
+    /// Classifies every row of a feature matrix, see [`LinearModel::predict`].
+    /// Fails if the matrix does not have [`LinearModel::n_feature`] columns.
+    pub fn predict_batch(&self, feature_matrix: &FeatureMatrix<F>) -> Result<Vec<F>> {
+        check_dimension(
+            "number of features",
+            self.n_feature(),
+            feature_matrix.n_cols(),
+        )?;
+        Ok(feature_matrix
+            .rows()
+            .map(|feature_vector| self.predict(feature_vector))
+            .collect())
+    }
+}
+
+impl<F: Float> From<(Vec<F>, F)> for LinearModel<F> {
+    /// Wraps the `(theta, theta_0)` tuple returned by the training functions.
+    fn from((theta, theta_0): (Vec<F>, F)) -> Self {
+        Self::new(theta, theta_0)
+    }
+}
+
+/// The training algorithms provided by this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    Perceptron,
+    AveragePerceptron,
+    Pegasos,
+    AveragePegasos,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [
+        Algorithm::Perceptron,
+        Algorithm::AveragePerceptron,
+        Algorithm::Pegasos,
+        Algorithm::AveragePegasos,
+    ];
+
+    /// Whether the algorithm is trained with a regularization parameter
+    /// `lambda`.
+    pub fn uses_lambda(self) -> bool {
+        matches!(self, Algorithm::Pegasos | Algorithm::AveragePegasos)
+    }
+
+    /// Whether the algorithm averages only a `tail` fraction of its final
+    /// steps.
+    pub fn uses_tail(self) -> bool {
+        self == Algorithm::AveragePegasos
+    }
+
+    /// The name used in reports and files, e.g. `average_perceptron`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Perceptron => "perceptron",
+            Algorithm::AveragePerceptron => "average_perceptron",
+            Algorithm::Pegasos => "pegasos",
+            Algorithm::AveragePegasos => "average_pegasos",
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Algorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == s)
+            .ok_or_else(|| Error::InvalidParameter {
+                name: "algorithm",
+                message: format!("unknown algorithm {s:?}"),
+            })
+    }
+}
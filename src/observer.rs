@@ -0,0 +1,74 @@
+use std::ops::ControlFlow;
+
+use crate::{DType, Float};
+
+/// State of a training loop right after one step.
+#[derive(Debug, Clone, Copy)]
+pub struct StepInfo<'a, F = DType> {
+    /// The current pass through the data, counting from 1.
+    pub epoch: usize,
+    /// Number of steps so far over all epochs, counting from 1.
+    pub step: usize,
+    /// Row index of the data point visited in this step.
+    pub sample: usize,
+    /// Whether the data point was misclassified (perceptron) or violated the
+    /// margin (Pegasos).
+    pub mistake: bool,
+    /// The current parameters. For the averaged algorithms these are the
+    /// latest iterate, not the average.
+    pub theta: &'a [F],
+    pub theta_0: F,
+}
+
+/// State of a training loop at the end of a full pass through the data.
+#[derive(Debug, Clone, Copy)]
+pub struct EpochInfo<'a, F = DType> {
+    /// The pass that just ended, counting from 1.
+    pub epoch: usize,
+    /// Number of steps so far over all epochs.
+    pub step: usize,
+    /// Number of mistakes made during this pass, see [`StepInfo::mistake`].
+    pub mistakes: usize,
+    /// The current parameters, see [`StepInfo::theta`].
+    pub theta: &'a [F],
+    pub theta_0: F,
+}
+
+/// Hooks called by the `*_with_options` training functions, e.g.
+/// [`crate::perceptron_with_options`], to log learning curves or stop
+/// training. They are passed as [`crate::options::TrainOptions::observer`].
+///
+/// Returning [`ControlFlow::Break`] ends training right away and the
+/// parameters reached so far are returned. Both hooks default to doing
+/// nothing; `()` is an observer that never interferes.
+pub trait Observer<F: Float = DType> {
+    /// Called after every step.
+    fn on_step(&mut self, _step: &StepInfo<'_, F>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called after every full pass through the data, unless a step ended
+    /// training during it.
+    fn on_epoch(&mut self, _epoch: &EpochInfo<'_, F>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+impl<F: Float> Observer<F> for () {}
+
+/// An observer recording a learning curve: the number of mistakes and the
+/// Euclidean norm of theta at the end of each epoch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EpochHistory<F = DType> {
+    pub mistakes: Vec<usize>,
+    pub theta_norms: Vec<F>,
+}
+
+impl<F: Float> Observer<F> for EpochHistory<F> {
+    fn on_epoch(&mut self, epoch: &EpochInfo<'_, F>) -> ControlFlow<()> {
+        self.mistakes.push(epoch.mistakes);
+        self.theta_norms
+            .push(epoch.theta.iter().map(|&x| x * x).sum::<F>().sqrt());
+        ControlFlow::Continue(())
+    }
+}
@@ -0,0 +1,361 @@
+use std::fs;
+
+use crate::averaging::{LazyAverage, RunningSum};
+use crate::error::Result;
+use crate::matrix::FeatureMatrix;
+use crate::model::{Algorithm, LinearModel};
+use crate::persist::{
+    counted_lines, expect_end, field, header, parse_error, parse_float, parse_value,
+};
+use crate::{
+    check_dimension, check_lambda, check_training_data, pegasos_single_step_update_in_place,
+    perceptron_single_step_update_in_place, DType, Float,
+};
+
+/// First line of every learner file, versioned like the model files of
+/// [`crate::persist`].
+const MAGIC: &str = "automatic_review_analyzer learner";
+pub const FORMAT_VERSION: u32 = 1;
+
+/// What an [`OnlineLearner`] averages, depending on its algorithm.
+#[derive(Debug, Clone)]
+pub(crate) enum Averaging {
+    None,
+    Lazy(LazyAverage),
+    Running(RunningSum),
+}
+
+/// A learner that keeps its training state between batches of data, so a
+/// model can be updated as new data arrives instead of being retrained on
+/// the whole history.
+///
+/// Each call to [`OnlineLearner::partial_fit`] makes one pass through the
+/// batch. The Pegasos step counter carries over between batches, so the
+/// step size keeps decaying as if all batches had been seen in one run,
+/// and so do the sums of the averaged algorithms. [`Algorithm::AveragePegasos`]
+/// averages every iterate since the learner was created, as a `tail` of `1`
+/// does, because the total number of steps is not known in advance.
+///
+/// [`OnlineLearner::save`] keeps all of this state, so a learner loaded in
+/// another process continues exactly where the saved one stopped.
+#[derive(Debug, Clone)]
+pub struct OnlineLearner<F = DType> {
+    pub(crate) algorithm: Algorithm,
+    pub(crate) lambda: F,
+    pub(crate) theta: Vec<F>,
+    pub(crate) theta_0: F,
+    pub(crate) steps: usize,
+    /// Number of steps after which [`Averaging::Running`] starts summing.
+    pub(crate) average_after: usize,
+    pub(crate) averaging: Averaging,
+}
+
+impl<F: Float> OnlineLearner<F> {
+    /// Creates a learner whose parameters are all zero.
+    ///
+    /// Args:
+    /// * `algorithm` - the algorithm to train with.
+    /// * `n_feature` - number of features of the data.
+    /// * `lambda` - the regularization parameter, ignored unless
+    ///   [`Algorithm::uses_lambda`].
+    ///
+    /// Fails if the algorithm uses `lambda` and it is negative or not finite.
+    pub fn new(algorithm: Algorithm, n_feature: usize, lambda: F) -> Result<Self> {
+        Self::from_model(algorithm, LinearModel::zeros(n_feature), lambda, 0)
+    }
+
+    /// Creates a learner that continues from an existing model.
+    ///
+    /// Args:
+    /// * `algorithm` - the algorithm to train with.
+    /// * `model` - the parameters to start from.
+    /// * `lambda` - the regularization parameter, ignored unless
+    ///   [`Algorithm::uses_lambda`].
+    /// * `steps` - number of steps the model has already been trained for,
+    ///   which sets the next Pegasos step size to `1 / sqrt(steps + 1)`.
+    ///
+    /// The averaged algorithms only average the iterates from here on, as
+    /// the sums behind `model` are not known. To continue a learner with
+    /// its sums, save it with [`OnlineLearner::save`] and use
+    /// [`OnlineLearner::load`] instead.
+    ///
+    /// Fails if the algorithm uses `lambda` and it is negative or not finite.
+    pub fn from_model(
+        algorithm: Algorithm,
+        model: LinearModel<F>,
+        lambda: F,
+        steps: usize,
+    ) -> Result<Self> {
+        if algorithm.uses_lambda() {
+            check_lambda(lambda)?;
+        }
+        let averaging = match algorithm {
+            Algorithm::Perceptron | Algorithm::Pegasos => Averaging::None,
+            Algorithm::AveragePerceptron => {
+                let mut average = LazyAverage::new(model.n_feature());
+                average.start_from(&model.theta, model.theta_0);
+                Averaging::Lazy(average)
+            }
+            Algorithm::AveragePegasos => Averaging::Running(RunningSum::new(model.n_feature())),
+        };
+        Ok(Self {
+            algorithm,
+            lambda,
+            theta: model.theta,
+            theta_0: model.theta_0,
+            steps,
+            average_after: steps,
+            averaging,
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Number of steps taken so far, including those given to
+    /// [`OnlineLearner::from_model`].
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Updates the learner with one pass through a batch of data, visiting
+    /// the rows in order.
+    ///
+    /// Args:
+    /// * `feature_matrix` - matrix describing the new data. Each row
+    ///   represents a single data point.
+    /// * `labels` - correct classification of each row, `1` or `-1`.
+    ///
+    /// Fails, leaving the learner unchanged, if the batch is invalid like
+    /// for [`crate::perceptron`] or does not have the learner's number of
+    /// features.
+    pub fn partial_fit(&mut self, feature_matrix: &FeatureMatrix<F>, labels: &[F]) -> Result<()> {
+        check_training_data(feature_matrix, labels)?;
+        check_dimension(
+            "number of features",
+            self.theta.len(),
+            feature_matrix.n_cols(),
+        )?;
+        for (feature_vector, &label) in feature_matrix.rows().zip(labels) {
+            self.step(feature_vector, label);
+        }
+        Ok(())
+    }
+
+    /// Updates the learner with a single data point, which must have been
+    /// checked already.
+    pub(crate) fn step(&mut self, feature_vector: &[F], label: F) {
+        self.steps += 1;
+        match self.algorithm {
+            Algorithm::Perceptron | Algorithm::AveragePerceptron => {
+                let mistake = perceptron_single_step_update_in_place(
+                    feature_vector,
+                    label,
+                    &mut self.theta,
+                    &mut self.theta_0,
+                );
+                if let Averaging::Lazy(average) = &mut self.averaging {
+                    average.step();
+                    if mistake {
+                        average.record(
+                            label,
+                            feature_vector
+                                .iter()
+                                .copied()
+                                .enumerate()
+                                .filter(|&(_, x)| x != F::ZERO),
+                        );
+                    }
+                }
+            }
+            Algorithm::Pegasos | Algorithm::AveragePegasos => {
+                let eta = F::ONE / F::from_usize(self.steps).sqrt();
+                pegasos_single_step_update_in_place(
+                    feature_vector,
+                    label,
+                    self.lambda,
+                    eta,
+                    &mut self.theta,
+                    &mut self.theta_0,
+                );
+                if let Averaging::Running(sum) = &mut self.averaging {
+                    if self.steps > self.average_after {
+                        sum.add(&self.theta, self.theta_0);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The model learned so far: the average of the iterates for the
+    /// averaged algorithms, or the latest iterate otherwise and before the
+    /// first step.
+    pub fn model(&self) -> LinearModel<F> {
+        let latest = || LinearModel::new(self.theta.clone(), self.theta_0);
+        match &self.averaging {
+            Averaging::None => latest(),
+            Averaging::Lazy(average) if average.steps() == 0 => latest(),
+            Averaging::Lazy(average) => average.average(&self.theta, self.theta_0).into(),
+            Averaging::Running(sum) if sum.count() == 0 => latest(),
+            Averaging::Running(sum) => sum.average().into(),
+        }
+    }
+}
+
+impl<F: Float> OnlineLearner<F> {
+    /// Formats the learner as text. The first line is the format header,
+    /// followed by one tab-separated `name value` line per field and one
+    /// line per feature:
+    ///
+    /// ```text
+    /// automatic_review_analyzer learner 1
+    /// algorithm      average_perceptron
+    /// lambda
+    /// steps          6
+    /// average_after  0
+    /// averaged       6
+    /// theta_0        -1
+    /// sum_0          -4
+    /// n_feature      2
+    /// 0.5            3
+    /// -0.75          -4.5
+    /// ```
+    ///
+    /// Each feature line holds the coefficient and, for the averaged
+    /// algorithms, its running sum. `lambda` is left empty for the
+    /// perceptrons, and `averaged` and `sum_0` for the algorithms that do
+    /// not average. Floats are written in their shortest form that parses
+    /// back to the same value, so nothing is lost.
+    pub fn to_text(&self) -> String {
+        format!("{MAGIC} {FORMAT_VERSION}\n{}", self.state_text())
+    }
+
+    /// Writes the text of [`OnlineLearner::to_text`] to `path`.
+    pub fn save(&self, path: &str) -> Result<()> {
+        Ok(fs::write(path, self.to_text())?)
+    }
+
+    /// Reads a learner written by [`OnlineLearner::save`].
+    pub fn load(path: &str) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the text of [`OnlineLearner::to_text`].
+    ///
+    /// Fails with [`crate::error::Error::Parse`] if the version is not
+    /// [`FORMAT_VERSION`], a field is missing or malformed, or the number of
+    /// feature lines differs from `n_feature`.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let end = contents.lines().count() + 1;
+        let mut lines = contents.lines().enumerate().map(|(i, line)| (i + 1, line));
+        header(&mut lines, end, MAGIC, FORMAT_VERSION, "learner")?;
+        let learner = Self::parse_state(&mut lines, end)?;
+        expect_end(&mut lines, "theta")?;
+        Ok(learner)
+    }
+
+    /// The lines of [`OnlineLearner::to_text`] after the header, which
+    /// [`crate::checkpoint::Checkpoint::to_text`] also ends with.
+    pub(crate) fn state_text(&self) -> String {
+        let lambda = if self.algorithm.uses_lambda() {
+            self.lambda.to_string()
+        } else {
+            String::new()
+        };
+        let (averaged, sum_0, sums) = match &self.averaging {
+            Averaging::None => (String::new(), String::new(), None),
+            Averaging::Lazy(average) => (
+                average.steps.to_string(),
+                average.weighted_0.to_string(),
+                Some(&average.weighted),
+            ),
+            Averaging::Running(sum) => {
+                (sum.count.to_string(), sum.sum_0.to_string(), Some(&sum.sum))
+            }
+        };
+        let mut text = format!(
+            "algorithm\t{}\nlambda\t{lambda}\nsteps\t{}\naverage_after\t{}\naveraged\t{averaged}\ntheta_0\t{}\nsum_0\t{sum_0}\nn_feature\t{}\n",
+            self.algorithm,
+            self.steps,
+            self.average_after,
+            self.theta_0,
+            self.theta.len()
+        );
+        for (i, value) in self.theta.iter().enumerate() {
+            match sums {
+                Some(sums) => text.push_str(&format!("{value}\t{}\n", sums[i])),
+                None => text.push_str(&format!("{value}\n")),
+            }
+        }
+        text
+    }
+
+    /// Parses the lines written by [`OnlineLearner::state_text`], leaving
+    /// any lines after them in `lines`.
+    pub(crate) fn parse_state<'a>(
+        lines: &mut impl Iterator<Item = (usize, &'a str)>,
+        end: usize,
+    ) -> Result<Self> {
+        let (line, algorithm) = field(lines, end, "algorithm")?;
+        let algorithm = algorithm
+            .parse::<Algorithm>()
+            .map_err(|err| parse_error(line, err.to_string()))?;
+        let (line, lambda) = field(lines, end, "lambda")?;
+        let lambda = if algorithm.uses_lambda() {
+            parse_float(line, "lambda", lambda)?
+        } else {
+            F::ZERO
+        };
+        let (line, steps) = field(lines, end, "steps")?;
+        let steps = parse_value::<usize>(line, "steps", steps)?;
+        let (line, average_after) = field(lines, end, "average_after")?;
+        let average_after = parse_value::<usize>(line, "average_after", average_after)?;
+        let (averaged_line, averaged) = field(lines, end, "averaged")?;
+        let (line, theta_0) = field(lines, end, "theta_0")?;
+        let theta_0 = parse_float(line, "theta_0", theta_0)?;
+        let (sum_0_line, sum_0) = field(lines, end, "sum_0")?;
+        let (line, n_feature) = field(lines, end, "n_feature")?;
+        let n_feature = parse_value::<usize>(line, "n_feature", n_feature)?;
+
+        let averages = !matches!(algorithm, Algorithm::Perceptron | Algorithm::Pegasos);
+        let mut sums = Vec::new();
+        let theta = counted_lines(lines, end, n_feature, "theta values", |line, entry| {
+            let (value, sum) = match (entry.split_once('\t'), averages) {
+                (Some((value, sum)), true) => (value, Some(sum)),
+                (None, false) => (entry, None),
+                _ => {
+                    return Err(parse_error(
+                        line,
+                        format!("{algorithm} learners need exactly one value per feature"),
+                    ))
+                }
+            };
+            let value = parse_float(line, "theta", value)?;
+            if let Some(sum) = sum {
+                sums.push(parse_value::<f64>(line, "sum", sum)?);
+            }
+            Ok(value)
+        })?;
+
+        let averaging = match algorithm {
+            Algorithm::Perceptron | Algorithm::Pegasos => Averaging::None,
+            Algorithm::AveragePerceptron => Averaging::Lazy(LazyAverage {
+                weighted: sums,
+                weighted_0: parse_value(sum_0_line, "sum_0", sum_0)?,
+                steps: parse_value(averaged_line, "averaged", averaged)?,
+            }),
+            Algorithm::AveragePegasos => Averaging::Running(RunningSum {
+                sum: sums,
+                sum_0: parse_value(sum_0_line, "sum_0", sum_0)?,
+                count: parse_value(averaged_line, "averaged", averaged)?,
+            }),
+        };
+        let mut learner =
+            Self::from_model(algorithm, LinearModel::new(theta, theta_0), lambda, steps)
+                .map_err(|err| parse_error(end, err.to_string()))?;
+        learner.average_after = average_after;
+        learner.averaging = averaging;
+        Ok(learner)
+    }
+}
@@ -0,0 +1,67 @@
+use std::borrow::Cow;
+
+use crate::error::Result;
+use crate::model::LinearModel;
+use crate::observer::Observer;
+use crate::order::get_order;
+use crate::{check_initial, check_order, DType, Float};
+
+/// Optional settings of a training run, taken by the `*_with_options`
+/// training functions, e.g. [`crate::perceptron_with_options`].
+///
+/// The default visits the samples in the order given by [`get_order`],
+/// reports to no observer and starts from zero, which is how the plain
+/// training functions train.
+pub struct TrainOptions<'a, F = DType> {
+    /// Permutation of the row indices of the feature matrix, visited in
+    /// this order during every iteration. Training fails if it is not one.
+    pub order: Option<&'a [usize]>,
+    /// Hooks called after every step and every epoch. Training ends early
+    /// as soon as one of them breaks, see [`Observer`].
+    pub observer: Option<&'a mut dyn Observer<F>>,
+    /// The model to start from instead of zero, e.g. one trained on other
+    /// data. Training fails if it is not finite or does not have one
+    /// coefficient per column of the feature matrix.
+    ///
+    /// Only the parameters carry over: the Pegasos step size schedule
+    /// starts over at `1` and the averaged algorithms average the iterates
+    /// that follow. Continuing an interrupted run exactly is what
+    /// [`crate::checkpoint::Checkpoint`] is for.
+    pub initial: Option<&'a LinearModel<F>>,
+}
+
+impl<F> Default for TrainOptions<'_, F> {
+    fn default() -> Self {
+        Self {
+            order: None,
+            observer: None,
+            initial: None,
+        }
+    }
+}
+
+impl<'a, F: Float> TrainOptions<'a, F> {
+    /// The order to visit `n_samples` samples in, checked to be a
+    /// permutation.
+    pub(crate) fn order_for(&self, n_samples: usize) -> Result<Cow<'a, [usize]>> {
+        match self.order {
+            Some(order) => {
+                check_order(order, n_samples)?;
+                Ok(Cow::Borrowed(order))
+            }
+            None => Ok(Cow::Owned(get_order(n_samples))),
+        }
+    }
+
+    /// The parameters to start from for `n_feature` features, checked to be
+    /// finite and of the right length.
+    pub(crate) fn initial_for(&self, n_feature: usize) -> Result<LinearModel<F>> {
+        match self.initial {
+            Some(initial) => {
+                check_initial(initial, n_feature)?;
+                Ok(initial.clone())
+            }
+            None => Ok(LinearModel::zeros(n_feature)),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs;
+
+use crate::error::{Error, Result};
+
+/// Permutation of the 200 toy samples used by the reference implementation.
+const ORDER_200: &str = include_str!("../data/200.txt");
+/// Permutation of the 4000 training reviews used by the reference implementation.
+const ORDER_4000: &str = include_str!("../data/4000.txt");
+
+/// Seed of the shuffle used for sizes without a fixed permutation.
+pub const DEFAULT_SEED: u64 = 1;
+
+/// Returns the order in which the training functions visit `n_samples` samples.
+///
+/// The permutations of `data/200.txt` and `data/4000.txt` are used for 200
+/// and 4000 samples; any other size gets the indices `0..n_samples` shuffled
+/// with [`DEFAULT_SEED`]. Both match the `get_order` of the reference
+/// implementation, so results can be compared with its outputs.
+///
+/// Args:
+/// * `n_samples` - the number of rows of the feature matrix.
+///
+/// Returns: a permutation of `0..n_samples`.
+pub fn get_order(n_samples: usize) -> Vec<usize> {
+    let fixed = match n_samples {
+        200 => ORDER_200,
+        4000 => ORDER_4000,
+        _ => return shuffled_order(n_samples, DEFAULT_SEED),
+    };
+    parse_order(fixed).expect("bundled order files are valid permutations")
+}
+
+/// Returns the indices `0..n_samples` in natural order.
+pub fn natural_order(n_samples: usize) -> Vec<usize> {
+    (0..n_samples).collect()
+}
+
+/// Returns the indices `0..n_samples` shuffled the way Python's
+/// `random.seed(seed); random.shuffle(indices)` does.
+pub fn shuffled_order(n_samples: usize, seed: u64) -> Vec<usize> {
+    let mut rng = MersenneTwister::new(seed);
+    let mut order = natural_order(n_samples);
+    for i in (1..n_samples).rev() {
+        let j = rng.below(i + 1);
+        order.swap(i, j);
+    }
+    order
+}
+
+/// Loads a permutation written as comma-separated indices on a single line,
+/// such as `data/200.txt`.
+pub fn load_order(path: &str) -> Result<Vec<usize>> {
+    parse_order(&fs::read_to_string(path)?)
+}
+
+/// Parses a comma-separated permutation, see [`load_order`].
+pub fn parse_order(contents: &str) -> Result<Vec<usize>> {
+    let order = contents
+        .trim()
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| {
+            s.trim().parse::<usize>().map_err(|_| Error::Parse {
+                line: 1,
+                message: format!("invalid index {s:?}"),
+            })
+        })
+        .collect::<Result<Vec<usize>>>()?;
+
+    let mut seen = vec![false; order.len()];
+    for &i in &order {
+        if i >= order.len() || seen[i] {
+            return Err(Error::Parse {
+                line: 1,
+                message: format!("index {i} makes the order not a permutation"),
+            });
+        }
+        seen[i] = true;
+    }
+    Ok(order)
+}
+
+/// MT19937 generator seeded like CPython's `random.seed` with an integer.
+struct MersenneTwister {
+    state: [u32; 624],
+    index: usize,
+}
+
+impl MersenneTwister {
+    const N: usize = 624;
+    const M: usize = 397;
+
+    fn new(seed: u64) -> Self {
+        let mut key = vec![seed as u32];
+        if seed >> 32 != 0 {
+            key.push((seed >> 32) as u32);
+        }
+
+        let mut state = [0u32; Self::N];
+        state[0] = 19650218;
+        for i in 1..Self::N {
+            state[i] = 1812433253u32
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30))
+                .wrapping_add(i as u32);
+        }
+
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..Self::N.max(key.len()) {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1664525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= Self::N {
+                state[0] = state[Self::N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..Self::N - 1 {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1566083941))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= Self::N {
+                state[0] = state[Self::N - 1];
+                i = 1;
+            }
+        }
+        state[0] = 0x80000000;
+
+        Self {
+            state,
+            index: Self::N,
+        }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= Self::N {
+            for i in 0..Self::N {
+                let y = (self.state[i] & 0x80000000) | (self.state[(i + 1) % Self::N] & 0x7fffffff);
+                let mut next = self.state[(i + Self::M) % Self::N] ^ (y >> 1);
+                if y & 1 != 0 {
+                    next ^= 0x9908b0df;
+                }
+                self.state[i] = next;
+            }
+            self.index = 0;
+        }
+        let mut y = self.state[self.index];
+        self.index += 1;
+
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c5680;
+        y ^= (y << 15) & 0xefc60000;
+        y ^ (y >> 18)
+    }
+
+    /// Uniform integer in `0..n` drawn like CPython's `_randbelow`, which
+    /// rejects samples of `n.bit_length()` random bits until one is below `n`.
+    fn below(&mut self, n: usize) -> usize {
+        let k = usize::BITS - n.leading_zeros();
+        debug_assert!(k <= 32, "orders longer than 2^32 are not supported");
+        loop {
+            let r = (self.next_u32() >> (32 - k)) as usize;
+            if r < n {
+                return r;
+            }
+        }
+    }
+}
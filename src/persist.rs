@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+use std::fs;
+
+use crate::error::{Error, Result};
+use crate::features::Dictionary;
+use crate::model::{Algorithm, LinearModel};
+use crate::{check_dimension, DType, Float};
+
+/// First line of every model file. The number is bumped whenever the layout
+/// changes so older binaries refuse files they cannot read.
+const MAGIC: &str = "automatic_review_analyzer model";
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A trained classifier together with everything needed to reuse it in
+/// another process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedModel<F = DType> {
+    pub algorithm: Algorithm,
+    /// Number of iterations through the training data.
+    pub t: usize,
+    /// Regularization parameter, only used by Pegasos.
+    pub lambda: Option<F>,
+    /// Fraction of the final steps that were averaged, only used by
+    /// averaged Pegasos.
+    pub tail: Option<F>,
+    pub model: LinearModel<F>,
+    /// The bag-of-words dictionary the model was trained against, if the
+    /// features were extracted from text.
+    pub vocabulary: Option<Dictionary>,
+}
+
+impl<F: Float> SavedModel<F> {
+    /// Bundles a trained model with its training settings.
+    ///
+    /// Fails if the vocabulary does not have one word per feature, or if
+    /// `lambda` or `tail` is given for an algorithm that does not use it or
+    /// missing for one that does.
+    pub fn new(
+        algorithm: Algorithm,
+        t: usize,
+        lambda: Option<F>,
+        tail: Option<F>,
+        model: LinearModel<F>,
+        vocabulary: Option<Dictionary>,
+    ) -> Result<Self> {
+        if let Some(vocabulary) = &vocabulary {
+            check_dimension("vocabulary size", model.n_feature(), vocabulary.len())?;
+        }
+        match (algorithm.uses_lambda(), lambda) {
+            (true, None) => {
+                return Err(Error::InvalidParameter {
+                    name: "lambda",
+                    message: format!("{algorithm} models need a lambda"),
+                })
+            }
+            (false, Some(_)) => {
+                return Err(Error::InvalidParameter {
+                    name: "lambda",
+                    message: format!("{algorithm} models take no lambda"),
+                })
+            }
+            _ => {}
+        }
+        match (algorithm.uses_tail(), tail) {
+            (true, None) => {
+                return Err(Error::InvalidParameter {
+                    name: "tail",
+                    message: format!("{algorithm} models need a tail"),
+                })
+            }
+            (false, Some(_)) => {
+                return Err(Error::InvalidParameter {
+                    name: "tail",
+                    message: format!("{algorithm} models take no tail"),
+                })
+            }
+            _ => {}
+        }
+        Ok(Self {
+            algorithm,
+            t,
+            lambda,
+            tail,
+            model,
+            vocabulary,
+        })
+    }
+
+    /// Formats the model as text. The first line is the format header,
+    /// followed by one tab-separated `name value` line per field and one
+    /// `theta_i word_i` line per feature:
+    ///
+    /// ```text
+    /// automatic_review_analyzer model 1
+    /// algorithm    pegasos
+    /// t            10
+    /// lambda       0.01
+    /// tail
+    /// theta_0      -0.25
+    /// n_feature    2
+    /// 0.5          great
+    /// -0.75        awful
+    /// ```
+    ///
+    /// `lambda` is left empty for the perceptrons, `tail` for every algorithm
+    /// but averaged Pegasos, and the word column is omitted when there is no
+    /// vocabulary. Floats are written in their
+    /// shortest form that parses back to the same value, so a model is only
+    /// read back exactly into the float type it was saved from.
+    pub fn to_text(&self) -> String {
+        let lambda = self.lambda.map(|l| l.to_string()).unwrap_or_default();
+        let tail = self.tail.map(|t| t.to_string()).unwrap_or_default();
+        let mut text = format!(
+            "{MAGIC} {FORMAT_VERSION}\nalgorithm\t{}\nt\t{}\nlambda\t{lambda}\ntail\t{tail}\ntheta_0\t{}\nn_feature\t{}\n",
+            self.algorithm,
+            self.t,
+            self.model.theta_0,
+            self.model.n_feature()
+        );
+        for (i, value) in self.model.theta.iter().enumerate() {
+            match self.vocabulary.as_ref().and_then(|v| v.word(i)) {
+                Some(word) => text.push_str(&format!("{value}\t{word}\n")),
+                None => text.push_str(&format!("{value}\n")),
+            }
+        }
+        text
+    }
+
+    /// Writes the text of [`SavedModel::to_text`] to `path`.
+    pub fn save(&self, path: &str) -> Result<()> {
+        Ok(fs::write(path, self.to_text())?)
+    }
+
+    /// Reads a model written by [`SavedModel::save`].
+    pub fn load(path: &str) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the text of [`SavedModel::to_text`].
+    ///
+    /// Fails with [`Error::Parse`] if the version is not [`FORMAT_VERSION`],
+    /// a field is missing or malformed, the number of theta lines differs
+    /// from `n_feature`, or only some of them have a word.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let end = contents.lines().count() + 1;
+        let mut lines = contents.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+        header(&mut lines, end, MAGIC, FORMAT_VERSION, "model")?;
+
+        let (line, algorithm) = field(&mut lines, end, "algorithm")?;
+        let algorithm = algorithm
+            .parse::<Algorithm>()
+            .map_err(|err| parse_error(line, err.to_string()))?;
+        let (line, t) = field(&mut lines, end, "t")?;
+        let t = parse_value::<usize>(line, "t", t)?;
+        let (line, lambda) = field(&mut lines, end, "lambda")?;
+        let lambda = match lambda {
+            "" => None,
+            lambda => Some(parse_float(line, "lambda", lambda)?),
+        };
+        let (line, tail) = field(&mut lines, end, "tail")?;
+        let tail = match tail {
+            "" => None,
+            tail => Some(parse_float(line, "tail", tail)?),
+        };
+        let (line, theta_0) = field(&mut lines, end, "theta_0")?;
+        let theta_0 = parse_float(line, "theta_0", theta_0)?;
+        let (line, n_feature) = field(&mut lines, end, "n_feature")?;
+        let n_feature = parse_value::<usize>(line, "n_feature", n_feature)?;
+
+        let mut words: Vec<String> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut has_words = None;
+        let theta = counted_lines(&mut lines, end, n_feature, "theta values", |line, entry| {
+            let (value, word) = match entry.split_once('\t') {
+                Some((value, word)) => (value, Some(word)),
+                None => (entry, None),
+            };
+            if *has_words.get_or_insert(word.is_some()) != word.is_some() {
+                return Err(parse_error(
+                    line,
+                    "words must be given for all features or none".to_string(),
+                ));
+            }
+            let value = parse_float(line, "theta", value)?;
+            if let Some(word) = word {
+                if !seen.insert(word) {
+                    return Err(parse_error(line, format!("repeated word {word:?}")));
+                }
+                words.push(word.to_string());
+            }
+            Ok(value)
+        })?;
+        expect_end(&mut lines, "theta")?;
+
+        let vocabulary = (!words.is_empty()).then(|| Dictionary::from_words(words));
+        Self::new(
+            algorithm,
+            t,
+            lambda,
+            tail,
+            LinearModel::new(theta, theta_0),
+            vocabulary,
+        )
+    }
+}
+
+pub(crate) fn parse_error(line: usize, message: String) -> Error {
+    Error::Parse { line, message }
+}
+
+/// Reads the `<magic> <version>` line at the start of a file of the given
+/// `kind` and checks that it has the expected version.
+pub(crate) fn header<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    end: usize,
+    magic: &str,
+    format_version: u32,
+    kind: &str,
+) -> Result<()> {
+    let (line, header) = lines.next().unwrap_or((end, ""));
+    let version = header
+        .strip_prefix(magic)
+        .map(str::trim)
+        .ok_or_else(|| parse_error(line, format!("not a {kind} file")))?;
+    if version != format_version.to_string() {
+        return Err(parse_error(
+            line,
+            format!("unsupported format version {version:?}, expected {format_version}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the next `name<TAB>value` line and returns its line number and value.
+pub(crate) fn field<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    end: usize,
+    name: &str,
+) -> Result<(usize, &'a str)> {
+    let (line, content) = lines
+        .next()
+        .ok_or_else(|| parse_error(end, format!("missing field {name:?}")))?;
+    match content.split_once('\t') {
+        Some((key, value)) if key == name => Ok((line, value)),
+        _ => Err(parse_error(line, format!("expected field {name:?}"))),
+    }
+}
+
+/// Parses the next `count` lines with `parse`, given the number and content
+/// of each, and fails if there are fewer. `what` names the values in the
+/// error.
+///
+/// The count is read from the file and not trusted to size allocations: a
+/// corrupt file would otherwise abort the process before it is checked.
+pub(crate) fn counted_lines<'a, T>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    end: usize,
+    count: usize,
+    what: &str,
+    mut parse: impl FnMut(usize, &'a str) -> Result<T>,
+) -> Result<Vec<T>> {
+    let mut values = Vec::new();
+    for (line, entry) in lines.by_ref().take(count) {
+        values.push(parse(line, entry)?);
+    }
+    if values.len() != count {
+        return Err(parse_error(
+            end,
+            format!("expected {count} {what}, found {}", values.len()),
+        ));
+    }
+    Ok(values)
+}
+
+/// Fails if anything but empty lines follows the last `what` line.
+pub(crate) fn expect_end<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    what: &str,
+) -> Result<()> {
+    match lines.find(|(_, line)| !line.is_empty()) {
+        Some((line, _)) => Err(parse_error(
+            line,
+            format!("unexpected content after {what}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn parse_value<T: std::str::FromStr>(line: usize, name: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| parse_error(line, format!("invalid {name} {value:?}")))
+}
+
+pub(crate) fn parse_float<F: Float>(line: usize, name: &str, value: &str) -> Result<F> {
+    let value = parse_value::<F>(line, name, value)?;
+    if !value.is_finite() {
+        return Err(parse_error(line, format!("{name} is {value}")));
+    }
+    Ok(value)
+}
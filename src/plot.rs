@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+use std::fs;
+
+use crate::error::{Error, Result};
+use crate::matrix::FeatureMatrix;
+use crate::{check_dimension, check_labels, Float};
+
+const WIDTH: f64 = 600.;
+const HEIGHT: f64 = 600.;
+const MARGIN: f64 = 50.;
+const POSITIVE_COLOR: &str = "#1f77b4";
+const NEGATIVE_COLOR: &str = "#d62728";
+
+/// Renders 2-D data points coloured by label, together with the decision
+/// boundary `theta · x + theta_0 = 0`, as an SVG document.
+///
+/// Args:
+/// * `algo_name` - name of the algorithm, used as the title of the plot.
+/// * `feature_matrix` - matrix with two columns, one row per data point.
+/// * `labels` - classification of each data point, `1` or `-1`.
+/// * `thetas` - the `(theta, theta_0)` tuple returned by a training function.
+///
+/// Returns: the SVG document. Fails if the data is not two-dimensional, if
+///     the labels do not match the data or if theta does not have two values.
+///     The boundary is left out when theta is zero.
+pub fn plot_toy_data<F: Float>(
+    algo_name: &str,
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    thetas: &(Vec<F>, F),
+) -> Result<String> {
+    check_dimension("number of features", 2, feature_matrix.n_cols())?;
+    check_dimension("number of labels", feature_matrix.n_rows(), labels.len())?;
+    check_dimension("length of theta", 2, thetas.0.len())?;
+    check_labels(labels)?;
+    if feature_matrix.is_empty() {
+        return Err(Error::EmptyDataset);
+    }
+
+    let bounds = Bounds::around(feature_matrix);
+    let mut svg = String::new();
+    push_line(
+        &mut svg,
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}">"#
+        ),
+    );
+    push_line(
+        &mut svg,
+        format!(
+            r#"<rect x="{MARGIN}" y="{MARGIN}" width="{}" height="{}" fill="white" stroke="black"/>"#,
+            WIDTH - 2. * MARGIN,
+            HEIGHT - 2. * MARGIN
+        ),
+    );
+    push_line(
+        &mut svg,
+        format!(
+            r#"<text x="{}" y="{}" text-anchor="middle" font-family="sans-serif" font-size="18">{}</text>"#,
+            WIDTH / 2.,
+            MARGIN / 2. + 6.,
+            escape(algo_name)
+        ),
+    );
+    for (x, anchor) in [(bounds.x_min, "start"), (bounds.x_max, "end")] {
+        push_line(
+            &mut svg,
+            format!(
+                r#"<text x="{}" y="{}" text-anchor="{anchor}" font-family="sans-serif" font-size="12">{x:.2}</text>"#,
+                bounds.to_svg(x, bounds.y_min).0,
+                HEIGHT - MARGIN + 16.
+            ),
+        );
+    }
+    for (y, baseline) in [(bounds.y_min, "auto"), (bounds.y_max, "hanging")] {
+        push_line(
+            &mut svg,
+            format!(
+                r#"<text x="{}" y="{}" text-anchor="end" dominant-baseline="{baseline}" font-family="sans-serif" font-size="12">{y:.2}</text>"#,
+                MARGIN - 4.,
+                bounds.to_svg(bounds.x_min, y).1
+            ),
+        );
+    }
+
+    for (feature_vector, &label) in feature_matrix.rows().zip(labels) {
+        let (x, y) = bounds.to_svg(feature_vector[0], feature_vector[1]);
+        let color = if label > F::ZERO {
+            POSITIVE_COLOR
+        } else {
+            NEGATIVE_COLOR
+        };
+        push_line(
+            &mut svg,
+            format!(r#"<circle cx="{x:.2}" cy="{y:.2}" r="4" fill="{color}"/>"#),
+        );
+    }
+
+    if let Some(((x1, y1), (x2, y2))) = bounds.clip_line(&thetas.0, thetas.1) {
+        let (x1, y1) = bounds.to_svg(x1, y1);
+        let (x2, y2) = bounds.to_svg(x2, y2);
+        push_line(
+            &mut svg,
+            format!(
+                r#"<line x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="black" stroke-width="2"/>"#
+            ),
+        );
+    }
+
+    for (i, (color, name)) in [(POSITIVE_COLOR, "+1"), (NEGATIVE_COLOR, "-1")]
+        .into_iter()
+        .enumerate()
+    {
+        let y = MARGIN + 16. + 18. * i as f64;
+        push_line(
+            &mut svg,
+            format!(
+                r#"<circle cx="{}" cy="{y}" r="4" fill="{color}"/>"#,
+                WIDTH - MARGIN - 40.
+            ),
+        );
+        push_line(
+            &mut svg,
+            format!(
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="12">{name}</text>"#,
+                WIDTH - MARGIN - 30.,
+                y + 4.
+            ),
+        );
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+/// Writes the SVG of [`plot_toy_data`] to `path`.
+pub fn save_toy_plot<F: Float>(
+    path: &str,
+    algo_name: &str,
+    feature_matrix: &FeatureMatrix<F>,
+    labels: &[F],
+    thetas: &(Vec<F>, F),
+) -> Result<()> {
+    let svg = plot_toy_data(algo_name, feature_matrix, labels, thetas)?;
+    Ok(fs::write(path, svg)?)
+}
+
+/// The region of data space shown in the plot.
+struct Bounds<F> {
+    x_min: F,
+    x_max: F,
+    y_min: F,
+    y_max: F,
+}
+
+impl<F: Float> Bounds<F> {
+    /// The bounding box of the points, padded by 10% on every side. The
+    /// feature matrix must not be empty.
+    fn around(feature_matrix: &FeatureMatrix<F>) -> Self {
+        let first = feature_matrix.row(0);
+        let (mut x_min, mut x_max) = (first[0], first[0]);
+        let (mut y_min, mut y_max) = (first[1], first[1]);
+        for feature_vector in feature_matrix.rows() {
+            x_min = x_min.min(feature_vector[0]);
+            x_max = x_max.max(feature_vector[0]);
+            y_min = y_min.min(feature_vector[1]);
+            y_max = y_max.max(feature_vector[1]);
+        }
+        let x_pad = ((x_max - x_min) * F::from_f64(0.1)).max(F::ONE);
+        let y_pad = ((y_max - y_min) * F::from_f64(0.1)).max(F::ONE);
+        Self {
+            x_min: x_min - x_pad,
+            x_max: x_max + x_pad,
+            y_min: y_min - y_pad,
+            y_max: y_max + y_pad,
+        }
+    }
+
+    /// Maps a point of data space to SVG coordinates, where y grows downwards.
+    fn to_svg(&self, x: F, y: F) -> (F, F) {
+        let plot_width = F::from_f64(WIDTH - 2. * MARGIN);
+        let plot_height = F::from_f64(HEIGHT - 2. * MARGIN);
+        (
+            F::from_f64(MARGIN) + (x - self.x_min) / (self.x_max - self.x_min) * plot_width,
+            F::from_f64(HEIGHT - MARGIN)
+                - (y - self.y_min) / (self.y_max - self.y_min) * plot_height,
+        )
+    }
+
+    /// Returns the end points of the segment of `theta · x + theta_0 = 0`
+    /// inside the bounds, if the line crosses them.
+    fn clip_line(&self, theta: &[F], theta_0: F) -> Option<((F, F), (F, F))> {
+        let (a, b) = (theta[0], theta[1]);
+        let mut points = Vec::with_capacity(4);
+        if b.abs() > F::TOLERANCE {
+            for x in [self.x_min, self.x_max] {
+                let y = -(a * x + theta_0) / b;
+                if (self.y_min..=self.y_max).contains(&y) {
+                    points.push((x, y));
+                }
+            }
+        }
+        if a.abs() > F::TOLERANCE {
+            for y in [self.y_min, self.y_max] {
+                let x = -(b * y + theta_0) / a;
+                if (self.x_min..=self.x_max).contains(&x) {
+                    points.push((x, y));
+                }
+            }
+        }
+        let first = *points.first()?;
+        let last = points.into_iter().max_by(|p, q| {
+            let (p, q) = (distance(first, *p), distance(first, *q));
+            p.partial_cmp(&q).unwrap_or(Ordering::Equal)
+        })?;
+        Some((first, last))
+    }
+}
+
+fn push_line(svg: &mut String, line: String) {
+    svg.push_str(&line);
+    svg.push('\n');
+}
+
+fn distance<F: Float>((x1, y1): (F, F), (x2, y2): (F, F)) -> F {
+    let (dx, dy) = (x1 - x2, y1 - y2);
+    (dx * dx + dy * dy).sqrt()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,280 @@
+use std::fs;
+use std::ops::Range;
+
+use crate::error::{Error, Result};
+use crate::{check_dimension, check_labels, DType};
+
+/// Column names expected, in order, on the first line of a review TSV file.
+pub const REVIEW_COLUMNS: [&str; 7] = [
+    "sentiment",
+    "productId",
+    "userId",
+    "summary",
+    "text",
+    "helpfulY",
+    "helpfulN",
+];
+
+/// A single product review as stored in the `reviews_*.tsv` datasets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Review {
+    /// `+1` for a positive review, `-1` for a negative one, `None` when the
+    /// column is empty (as in `reviews_submit.tsv`).
+    pub sentiment: Option<i8>,
+    pub product_id: String,
+    pub user_id: String,
+    pub summary: String,
+    pub text: String,
+    pub helpful_y: u32,
+    pub helpful_n: u32,
+}
+
+impl Review {
+    /// Returns the sentiment as a label usable by the training functions.
+    pub fn label(&self) -> Option<DType> {
+        self.sentiment.map(DType::from)
+    }
+}
+
+/// Loads every review from a tab-separated file with the header
+/// `sentiment, productId, userId, summary, text, helpfulY, helpfulN`.
+///
+/// Args:
+/// * `path` - path of the TSV file, e.g. `data/reviews_train.tsv`.
+///
+/// Returns: the reviews in file order. Malformed content is reported as an
+///     [`Error::Parse`] mentioning the offending line.
+///
+/// The datasets are Latin-1 encoded, so every byte is decoded as the code
+/// point of the same value.
+pub fn load_reviews(path: &str) -> Result<Vec<Review>> {
+    parse_reviews(&decode_latin1(&fs::read(path)?))
+}
+
+/// Parses the content of a review TSV file, see [`load_reviews`].
+pub fn parse_reviews(contents: &str) -> Result<Vec<Review>> {
+    parse_review_records(contents)?
+        .into_iter()
+        .map(|record| parse_review(record.line, record.fields))
+        .collect()
+}
+
+/// Splits a review TSV file into records after checking its header, which
+/// is not included in the result.
+fn parse_review_records(contents: &str) -> Result<Vec<Record>> {
+    let mut records = parse_tsv(contents)?;
+    if records.is_empty() {
+        return Err(invalid_data(1, "missing header".to_string()));
+    }
+    let header = records.remove(0);
+    if header.fields != REVIEW_COLUMNS {
+        return Err(invalid_data(
+            header.line,
+            format!(
+                "expected header {:?}, found {:?}",
+                REVIEW_COLUMNS, header.fields
+            ),
+        ));
+    }
+    Ok(records)
+}
+
+/// Extracts the labels of labeled reviews, failing on the first review whose
+/// `sentiment` column is empty.
+pub fn labels(reviews: &[Review]) -> Result<Vec<DType>> {
+    reviews
+        .iter()
+        .enumerate()
+        .map(|(i, review)| review.label().ok_or(Error::MissingLabel { index: i }))
+        .collect()
+}
+
+/// Fills the empty `sentiment` column of a review file, such as
+/// `data/reviews_submit.tsv`, with predicted labels and writes the result.
+///
+/// Args:
+/// * `input_path` - path of the review TSV file to fill.
+/// * `output_path` - path the filled file is written to.
+/// * `preds` - predicted label of each review, `1` or `-1`, in file order.
+///
+/// Every byte outside of the `sentiment` fields, including the header,
+/// quoting and line endings, is copied unchanged. Fails if the file is
+/// malformed or if there is not one prediction per review.
+pub fn write_submission(input_path: &str, output_path: &str, preds: &[DType]) -> Result<()> {
+    let contents = fill_sentiment(&decode_latin1(&fs::read(input_path)?), preds)?;
+    Ok(fs::write(output_path, encode_latin1(&contents))?)
+}
+
+/// Replaces the `sentiment` field of every review in `contents` by the
+/// corresponding prediction, see [`write_submission`].
+pub fn fill_sentiment(contents: &str, preds: &[DType]) -> Result<String> {
+    let records = parse_review_records(contents)?;
+    check_dimension("number of predictions", records.len(), preds.len())?;
+    check_labels(preds)?;
+    for record in &records {
+        parse_review(record.line, record.fields.clone())?;
+    }
+
+    let mut filled = String::with_capacity(contents.len() + 2 * preds.len());
+    let mut copied = 0;
+    for (record, &pred) in records.iter().zip(preds) {
+        filled.push_str(&contents[copied..record.first_field.start]);
+        filled.push_str(if pred > 0 as DType { "1" } else { "-1" });
+        copied = record.first_field.end;
+    }
+    filled.push_str(&contents[copied..]);
+    Ok(filled)
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Inverse of [`decode_latin1`]. Characters outside of Latin-1 cannot come
+/// from a decoded file and are written as `?`.
+fn encode_latin1(contents: &str) -> Vec<u8> {
+    contents
+        .chars()
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect()
+}
+
+fn parse_review(line: usize, fields: Vec<String>) -> Result<Review> {
+    if fields.len() != REVIEW_COLUMNS.len() {
+        return Err(invalid_data(
+            line,
+            format!(
+                "expected {} fields, found {}",
+                REVIEW_COLUMNS.len(),
+                fields.len()
+            ),
+        ));
+    }
+    let mut fields = fields.into_iter();
+    let mut next = || fields.next().unwrap_or_default();
+
+    let sentiment = match next().as_str() {
+        "" => None,
+        "1" | "+1" => Some(1),
+        "-1" => Some(-1),
+        other => return Err(invalid_data(line, format!("invalid sentiment {other:?}"))),
+    };
+    let product_id = next();
+    let user_id = next();
+    let summary = next();
+    let text = next();
+    let helpful_y = parse_count(line, "helpfulY", &next())?;
+    let helpful_n = parse_count(line, "helpfulN", &next())?;
+
+    Ok(Review {
+        sentiment,
+        product_id,
+        user_id,
+        summary,
+        text,
+        helpful_y,
+        helpful_n,
+    })
+}
+
+fn parse_count(line: usize, column: &str, s: &str) -> Result<u32> {
+    s.parse()
+        .map_err(|_| invalid_data(line, format!("invalid {column} count {s:?}")))
+}
+
+fn invalid_data(line: usize, message: String) -> Error {
+    Error::Parse { line, message }
+}
+
+/// A record of a TSV file as returned by [`parse_tsv`].
+struct Record {
+    /// Line the record starts on.
+    line: usize,
+    fields: Vec<String>,
+    /// Byte range of the first field in the content, including any quotes.
+    first_field: Range<usize>,
+}
+
+/// Splits tab-separated content into records. Fields wrapped in double
+/// quotes may contain tabs, line breaks and `""` escapes for a literal quote.
+fn parse_tsv(contents: &str) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    let mut chars = contents.chars().peekable();
+    let mut line = 1;
+    let mut offset = 0;
+
+    while chars.peek().is_some() {
+        let start_line = line;
+        let mut fields = Vec::new();
+        let mut first_field = offset..offset;
+        loop {
+            let mut field = String::new();
+            let field_start = offset;
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                offset += 1;
+                loop {
+                    let c = chars.next();
+                    offset += c.map_or(0, char::len_utf8);
+                    match c {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            offset += 1;
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => {
+                            return Err(invalid_data(
+                                start_line,
+                                "unterminated quoted field".to_string(),
+                            ))
+                        }
+                    }
+                }
+                if !matches!(chars.peek(), None | Some('\t' | '\r' | '\n')) {
+                    return Err(invalid_data(
+                        line,
+                        "unexpected character after closing quote".to_string(),
+                    ));
+                }
+            }
+            while let Some(&c) = chars.peek() {
+                if matches!(c, '\t' | '\r' | '\n') {
+                    break;
+                }
+                field.push(c);
+                chars.next();
+                offset += c.len_utf8();
+            }
+            if fields.is_empty() {
+                first_field = field_start..offset;
+            }
+            fields.push(field);
+
+            let c = chars.next();
+            offset += c.map_or(0, char::len_utf8);
+            match c {
+                Some('\t') => continue,
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                    offset += 1;
+                }
+                _ => {}
+            }
+            line += 1;
+            break;
+        }
+        records.push(Record {
+            line: start_line,
+            fields,
+            first_field,
+        });
+    }
+    Ok(records)
+}
@@ -0,0 +1,587 @@
+use crate::averaging::LazyAverage;
+use crate::error::{Error, Result};
+use crate::matrix::{FeatureMatrix, ShapeError};
+use crate::model::LinearModel;
+use crate::observer::{EpochInfo, StepInfo};
+use crate::options::TrainOptions;
+use crate::{check_dimension, check_labels, check_lambda, DType, Float};
+
+/// A sparse feature vector given by its non-zero entries, borrowed from a
+/// [`SparseMatrix`] row or from caller-owned arrays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SparseVector<'a, F = DType> {
+    indices: &'a [usize],
+    values: &'a [F],
+}
+
+impl<'a, F: Float> SparseVector<'a, F> {
+    /// Creates a sparse vector from strictly increasing `indices` and the
+    /// `values` stored at them.
+    pub fn new(indices: &'a [usize], values: &'a [F]) -> std::result::Result<Self, ShapeError> {
+        validate_entries(indices, values, usize::MAX)?;
+        Ok(Self { indices, values })
+    }
+
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn indices(&self) -> &'a [usize] {
+        self.indices
+    }
+
+    pub fn values(&self) -> &'a [F] {
+        self.values
+    }
+
+    /// Iterates over the `(index, value)` pairs of the stored entries.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, F)> + 'a {
+        self.indices
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+
+    /// Computes the dot product with a dense array in O(nnz).
+    pub fn dot(&self, dense: &[F]) -> F {
+        self.iter().map(|(i, x)| x * dense[i]).sum::<F>()
+    }
+
+    /// Returns the dense array of length `dim` holding the same values.
+    pub fn to_dense(&self, dim: usize) -> Vec<F> {
+        let mut dense = vec![F::ZERO; dim];
+        for (i, x) in self.iter() {
+            dense[i] = x;
+        }
+        dense
+    }
+}
+
+/// A sparse feature matrix in compressed sparse row format.
+/// Each row represents a single data point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix<F = DType> {
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<F>,
+    n_cols: usize,
+}
+
+impl<F: Float> SparseMatrix<F> {
+    /// Creates a matrix with `n_cols` columns and no rows.
+    pub fn new(n_cols: usize) -> Self {
+        Self {
+            indptr: vec![0],
+            indices: Vec::new(),
+            values: Vec::new(),
+            n_cols,
+        }
+    }
+
+    /// Creates a matrix holding the non-zero values of a dense matrix.
+    pub fn from_dense(feature_matrix: &FeatureMatrix<F>) -> Self {
+        let mut matrix = Self::new(feature_matrix.n_cols());
+        for row in feature_matrix.rows() {
+            for (i, &x) in row.iter().enumerate() {
+                if x != F::ZERO {
+                    matrix.indices.push(i);
+                    matrix.values.push(x);
+                }
+            }
+            matrix.indptr.push(matrix.indices.len());
+        }
+        matrix
+    }
+
+    /// Appends a row given by strictly increasing `indices`, all below
+    /// [`SparseMatrix::n_cols`], and the `values` stored at them.
+    pub fn push_row(
+        &mut self,
+        indices: &[usize],
+        values: &[F],
+    ) -> std::result::Result<(), ShapeError> {
+        validate_entries(indices, values, self.n_cols)?;
+        self.indices.extend_from_slice(indices);
+        self.values.extend_from_slice(values);
+        self.indptr.push(self.indices.len());
+        Ok(())
+    }
+
+    /// Returns `(n_rows, n_cols)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.n_rows(), self.n_cols)
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.indptr.len() - 1
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n_rows() == 0
+    }
+
+    /// Number of stored entries over all rows.
+    pub fn nnz(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns the feature vector of the ith data point.
+    pub fn row(&self, i: usize) -> SparseVector<'_, F> {
+        let range = self.indptr[i]..self.indptr[i + 1];
+        SparseVector {
+            indices: &self.indices[range.clone()],
+            values: &self.values[range],
+        }
+    }
+
+    /// Iterates over the rows in order.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = SparseVector<'_, F>> + '_ {
+        (0..self.n_rows()).map(move |i| self.row(i))
+    }
+
+    /// Returns the dense matrix holding the same values.
+    pub fn to_dense(&self) -> FeatureMatrix<F> {
+        let mut feature_matrix = FeatureMatrix::zeros(self.n_rows(), self.n_cols);
+        for (i, row) in self.rows().enumerate() {
+            let dense = feature_matrix.row_mut(i);
+            for (j, x) in row.iter() {
+                dense[j] = x;
+            }
+        }
+        feature_matrix
+    }
+}
+
+fn validate_entries<F: Float>(
+    indices: &[usize],
+    values: &[F],
+    n_cols: usize,
+) -> std::result::Result<(), ShapeError> {
+    if indices.len() != values.len() {
+        return Err(ShapeError::DataLength {
+            expected: indices.len(),
+            found: values.len(),
+        });
+    }
+    for (position, &index) in indices.iter().enumerate() {
+        if index >= n_cols {
+            return Err(ShapeError::IndexOutOfBounds { index, n_cols });
+        }
+        if position > 0 && indices[position - 1] >= index {
+            return Err(ShapeError::UnsortedIndices { position });
+        }
+    }
+    Ok(())
+}
+
+/// Checks a sparse training set like [`crate::perceptron`] checks a dense one.
+fn check_training_data<F: Float>(feature_matrix: &SparseMatrix<F>, labels: &[F]) -> Result<()> {
+    if feature_matrix.is_empty() {
+        return Err(Error::EmptyDataset);
+    }
+    check_dimension("number of labels", feature_matrix.n_rows(), labels.len())?;
+    check_labels(labels)?;
+    for (row, feature_vector) in feature_matrix.rows().enumerate() {
+        if let Some((col, value)) = feature_vector.iter().find(|(_, x)| !x.is_finite()) {
+            return Err(Error::NonFiniteValue {
+                row,
+                col,
+                value: value.to_f64(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// A dense parameter vector stored as `scale * values`, so that multiplying
+/// it by a constant costs O(1) instead of O(d).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaledVector<F = DType> {
+    scale: F,
+    values: Vec<F>,
+}
+
+impl<F: Float> ScaledVector<F> {
+    /// Below this magnitude the scale is folded back into the values to
+    /// avoid losing precision.
+    fn min_scale() -> F {
+        F::from_f64(1e-6)
+    }
+
+    pub fn zeros(dim: usize) -> Self {
+        Self::from_vec(vec![F::ZERO; dim])
+    }
+
+    pub fn from_vec(values: Vec<F>) -> Self {
+        Self {
+            scale: F::ONE,
+            values,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the ith coordinate.
+    pub fn get(&self, i: usize) -> F {
+        self.scale * self.values[i]
+    }
+
+    /// Computes the dot product with a sparse vector in O(nnz).
+    pub fn dot(&self, x: SparseVector<'_, F>) -> F {
+        self.scale * x.dot(&self.values)
+    }
+
+    /// Multiplies every coordinate by `factor`.
+    pub fn scale_by(&mut self, factor: F) {
+        self.scale *= factor;
+        if self.scale == F::ZERO {
+            self.values.fill(F::ZERO);
+            self.scale = F::ONE;
+        } else if self.scale.abs() < Self::min_scale() {
+            for v in self.values.iter_mut() {
+                *v *= self.scale;
+            }
+            self.scale = F::ONE;
+        }
+    }
+
+    /// Adds `coefficient * x` in O(nnz).
+    pub fn add_scaled(&mut self, coefficient: F, x: SparseVector<'_, F>) {
+        let coefficient = coefficient / self.scale;
+        for (i, value) in x.iter() {
+            self.values[i] += coefficient * value;
+        }
+    }
+
+    /// Returns the plain dense array.
+    pub fn to_vec(&self) -> Vec<F> {
+        self.values.iter().map(|&v| self.scale * v).collect()
+    }
+}
+
+/// Finds the hinge loss on a single sparse data point, see
+/// [`crate::hinge_loss_single`].
+pub fn hinge_loss_single_sparse<F: Float>(
+    feature_vector: SparseVector<'_, F>,
+    label: F,
+    theta: &[F],
+    theta_0: F,
+) -> F {
+    let output = feature_vector.dot(theta) + theta_0;
+    (F::ONE - output * label).max(F::ZERO)
+}
+
+/// Finds the hinge loss averaged over a sparse dataset, see
+/// [`crate::hinge_loss_full`].
+pub fn hinge_loss_full_sparse<F: Float>(
+    feature_matrix: &SparseMatrix<F>,
+    labels: &[F],
+    theta: &[F],
+    theta_0: F,
+) -> Result<F> {
+    if feature_matrix.is_empty() {
+        return Err(Error::EmptyDataset);
+    }
+    check_dimension("number of labels", feature_matrix.n_rows(), labels.len())?;
+    check_dimension("length of theta", feature_matrix.n_cols(), theta.len())?;
+    Ok(feature_matrix
+        .rows()
+        .zip(labels.iter())
+        .map(|(feature_vector, &label)| {
+            hinge_loss_single_sparse(feature_vector, label, theta, theta_0)
+        })
+        .sum::<F>()
+        / F::from_usize(labels.len()))
+}
+
+/// Updates `theta` and `theta_0` in place via a single step of the
+/// perceptron algorithm, touching only the non-zero features of the data point.
+///
+/// Args:
+/// * `feature_vector` - sparse vector describing a single data point.
+/// * `label` - the correct classification of the feature vector.
+/// * `theta` - the current theta, updated in place.
+/// * `theta_0` - the current theta_0, updated in place.
+///
+/// Returns: whether the data point was misclassified and the parameters updated.
+pub fn perceptron_single_step_update_sparse<F: Float>(
+    feature_vector: SparseVector<'_, F>,
+    label: F,
+    theta: &mut [F],
+    theta_0: &mut F,
+) -> bool {
+    let output = feature_vector.dot(theta) + *theta_0;
+    if label * output > F::TOLERANCE {
+        return false;
+    }
+    for (i, x) in feature_vector.iter() {
+        theta[i] += label * x;
+    }
+    *theta_0 += label;
+    true
+}
+
+/// Updates `theta` and `theta_0` in place via a single step of the Pegasos
+/// algorithm. Keeping `theta` as a [`ScaledVector`] makes the regularization
+/// shrink O(1), so the whole step costs O(nnz).
+///
+/// Args:
+/// * `feature_vector` - sparse vector describing a single data point.
+/// * `label` - the correct classification of the feature vector.
+/// * `lambda` - the lambda value being used to update the parameters.
+/// * `eta` - learning rate to update parameters.
+/// * `theta` - the current theta, updated in place.
+/// * `theta_0` - the current theta_0, updated in place.
+///
+/// Returns: whether the margin was violated and the data point added to theta.
+pub fn pegasos_single_step_update_sparse<F: Float>(
+    feature_vector: SparseVector<'_, F>,
+    label: F,
+    lambda: F,
+    eta: F,
+    theta: &mut ScaledVector<F>,
+    theta_0: &mut F,
+) -> bool {
+    let is_violation = label * (theta.dot(feature_vector) + *theta_0) <= F::ONE;
+    theta.scale_by(F::ONE - eta * lambda);
+    if is_violation {
+        theta.add_scaled(eta * label, feature_vector);
+        *theta_0 += eta * label;
+    }
+    is_violation
+}
+
+/// Runs the full perceptron algorithm on a sparse dataset, see
+/// [`crate::perceptron`]. Each step costs O(nnz) of the visited data point.
+pub fn perceptron_sparse<F: Float>(
+    feature_matrix: &SparseMatrix<F>,
+    labels: &[F],
+    t: usize,
+) -> Result<(Vec<F>, F)> {
+    perceptron_sparse_with_options(feature_matrix, labels, t, TrainOptions::default())
+}
+
+/// Runs the perceptron algorithm on a sparse dataset like
+/// [`crate::perceptron_with_options`], with the order, observer and initial
+/// parameters given by `options`.
+pub fn perceptron_sparse_with_options<F: Float>(
+    feature_matrix: &SparseMatrix<F>,
+    labels: &[F],
+    t: usize,
+    options: TrainOptions<'_, F>,
+) -> Result<(Vec<F>, F)> {
+    check_training_data(feature_matrix, labels)?;
+    let order = options.order_for(feature_matrix.n_rows())?;
+    let LinearModel {
+        mut theta,
+        mut theta_0,
+    } = options.initial_for(feature_matrix.n_cols())?;
+    let mut no_observer = ();
+    let observer = options.observer.unwrap_or(&mut no_observer);
+    let mut step = 0;
+
+    'epochs: for epoch in 1..=t {
+        let mut mistakes = 0;
+        for &i in order.iter() {
+            step += 1;
+            let mistake = perceptron_single_step_update_sparse(
+                feature_matrix.row(i),
+                labels[i],
+                &mut theta,
+                &mut theta_0,
+            );
+            mistakes += usize::from(mistake);
+            let info = StepInfo {
+                epoch,
+                step,
+                sample: i,
+                mistake,
+                theta: &theta,
+                theta_0,
+            };
+            if observer.on_step(&info).is_break() {
+                break 'epochs;
+            }
+        }
+        let info = EpochInfo {
+            epoch,
+            step,
+            mistakes,
+            theta: &theta,
+            theta_0,
+        };
+        if observer.on_epoch(&info).is_break() {
+            break;
+        }
+    }
+    Ok((theta, theta_0))
+}
+
+/// Runs the average perceptron algorithm on a sparse dataset, see
+/// [`crate::average_perceptron`]. The average is kept lazily, so each step
+/// costs O(nnz) of the visited data point.
+pub fn average_perceptron_sparse<F: Float>(
+    feature_matrix: &SparseMatrix<F>,
+    labels: &[F],
+    t: usize,
+) -> Result<(Vec<F>, F)> {
+    average_perceptron_sparse_with_options(feature_matrix, labels, t, TrainOptions::default())
+}
+
+/// Runs the average perceptron algorithm on a sparse dataset like
+/// [`crate::average_perceptron_with_options`], with the order, observer and
+/// initial parameters given by `options`.
+pub fn average_perceptron_sparse_with_options<F: Float>(
+    feature_matrix: &SparseMatrix<F>,
+    labels: &[F],
+    t: usize,
+    options: TrainOptions<'_, F>,
+) -> Result<(Vec<F>, F)> {
+    check_training_data(feature_matrix, labels)?;
+    let order = options.order_for(feature_matrix.n_rows())?;
+    let initial = options.initial_for(feature_matrix.n_cols())?;
+    if t == 0 {
+        return Err(Error::InvalidParameter {
+            name: "t",
+            message: "the average over zero iterations is undefined".to_string(),
+        });
+    }
+    let mut no_observer = ();
+    let observer = options.observer.unwrap_or(&mut no_observer);
+    let mut average = LazyAverage::new(feature_matrix.n_cols());
+    average.start_from(&initial.theta, initial.theta_0);
+    let LinearModel {
+        mut theta,
+        mut theta_0,
+    } = initial;
+    let mut step = 0;
+
+    'epochs: for epoch in 1..=t {
+        let mut mistakes = 0;
+        for &i in order.iter() {
+            step += 1;
+            let feature_vector = feature_matrix.row(i);
+            average.step();
+            let mistake = perceptron_single_step_update_sparse(
+                feature_vector,
+                labels[i],
+                &mut theta,
+                &mut theta_0,
+            );
+            if mistake {
+                mistakes += 1;
+                average.record(labels[i], feature_vector.iter());
+            }
+            let info = StepInfo {
+                epoch,
+                step,
+                sample: i,
+                mistake,
+                theta: &theta,
+                theta_0,
+            };
+            if observer.on_step(&info).is_break() {
+                break 'epochs;
+            }
+        }
+        let info = EpochInfo {
+            epoch,
+            step,
+            mistakes,
+            theta: &theta,
+            theta_0,
+        };
+        if observer.on_epoch(&info).is_break() {
+            break;
+        }
+    }
+    Ok(average.average(&theta, theta_0))
+}
+
+/// Runs the Pegasos algorithm on a sparse dataset, see [`crate::pegasos`].
+/// Each step costs O(nnz) of the visited data point.
+pub fn pegasos_sparse<F: Float>(
+    feature_matrix: &SparseMatrix<F>,
+    labels: &[F],
+    t: usize,
+    lambda: F,
+) -> Result<(Vec<F>, F)> {
+    pegasos_sparse_with_options(feature_matrix, labels, t, lambda, TrainOptions::default())
+}
+
+/// Runs the Pegasos algorithm on a sparse dataset like
+/// [`crate::pegasos_with_options`], with the order, observer and initial
+/// parameters given by `options`.
+///
+/// The observer is given theta as a plain array, so with one every step
+/// costs O(d) instead of O(nnz).
+pub fn pegasos_sparse_with_options<F: Float>(
+    feature_matrix: &SparseMatrix<F>,
+    labels: &[F],
+    t: usize,
+    lambda: F,
+    options: TrainOptions<'_, F>,
+) -> Result<(Vec<F>, F)> {
+    check_training_data(feature_matrix, labels)?;
+    let order = options.order_for(feature_matrix.n_rows())?;
+    check_lambda(lambda)?;
+    let initial = options.initial_for(feature_matrix.n_cols())?;
+    let mut theta = ScaledVector::from_vec(initial.theta);
+    let mut theta_0 = initial.theta_0;
+    let mut observer = options.observer;
+    let mut count = 0;
+
+    'epochs: for epoch in 1..=t {
+        let mut mistakes = 0;
+        for &i in order.iter() {
+            count += 1;
+            let eta = F::ONE / F::from_usize(count).sqrt();
+            let mistake = pegasos_single_step_update_sparse(
+                feature_matrix.row(i),
+                labels[i],
+                lambda,
+                eta,
+                &mut theta,
+                &mut theta_0,
+            );
+            mistakes += usize::from(mistake);
+            if let Some(observer) = observer.as_deref_mut() {
+                let info = StepInfo {
+                    epoch,
+                    step: count,
+                    sample: i,
+                    mistake,
+                    theta: &theta.to_vec(),
+                    theta_0,
+                };
+                if observer.on_step(&info).is_break() {
+                    break 'epochs;
+                }
+            }
+        }
+        if let Some(observer) = observer.as_deref_mut() {
+            let info = EpochInfo {
+                epoch,
+                step: count,
+                mistakes,
+                theta: &theta.to_vec(),
+                theta_0,
+            };
+            if observer.on_epoch(&info).is_break() {
+                break;
+            }
+        }
+    }
+    Ok((theta.to_vec(), theta_0))
+}
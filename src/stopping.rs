@@ -0,0 +1,165 @@
+use std::ops::ControlFlow;
+
+use crate::error::{Error, Result};
+use crate::evaluation::accuracy;
+use crate::matrix::FeatureMatrix;
+use crate::model::LinearModel;
+use crate::observer::{EpochInfo, Observer};
+use crate::{check_dimension, check_training_data, classify, DType, Float};
+
+/// When to end training before the maximum number of epochs.
+#[derive(Debug, Clone, Copy)]
+pub enum StoppingRule<'a, F = DType> {
+    /// Run every epoch, like the plain training functions.
+    Fixed,
+    /// Stop after the first pass through the data without mistakes, as the
+    /// data is then separated and later passes would not change anything.
+    Converged,
+    /// Stop once the accuracy on a validation set has not improved for
+    /// `patience` epochs in a row.
+    Patience {
+        feature_matrix: &'a FeatureMatrix<F>,
+        labels: &'a [F],
+        patience: usize,
+    },
+}
+
+impl<F: Float> StoppingRule<'_, F> {
+    /// Checks the validation data and patience against a training set with
+    /// `n_feature` features.
+    pub(crate) fn check(&self, n_feature: usize) -> Result<()> {
+        if let StoppingRule::Patience {
+            feature_matrix,
+            labels,
+            patience,
+        } = *self
+        {
+            check_training_data(feature_matrix, labels)?;
+            check_dimension("number of features", n_feature, feature_matrix.n_cols())?;
+            if patience == 0 {
+                return Err(Error::InvalidParameter {
+                    name: "patience",
+                    message: "must be at least one epoch".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of [`crate::perceptron_early_stopping`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarlyStoppingResult<F = DType> {
+    /// The `(theta, theta_0)` tuple reached when training stopped, or with
+    /// [`StoppingRule::Patience`] the one with the best validation accuracy.
+    pub thetas: (Vec<F>, F),
+    /// The epoch `thetas` was reached at, which for
+    /// [`StoppingRule::Patience`] is usually `patience` epochs before
+    /// training stopped.
+    pub best_epoch: usize,
+    /// Number of epochs actually run.
+    pub epochs_run: usize,
+}
+
+/// An observer that ends training according to a [`StoppingRule`] and
+/// counts the epochs actually run.
+///
+/// The validation accuracy of [`StoppingRule::Patience`] is that of the
+/// latest iterate, see [`crate::observer::StepInfo::theta`], and a copy of
+/// the parameters with the best accuracy is kept, as the iterates after it
+/// may be worse.
+#[derive(Debug)]
+pub struct EarlyStopping<'a, F = DType> {
+    rule: StoppingRule<'a, F>,
+    epochs: usize,
+    best_accuracy: Option<F>,
+    best: Option<(LinearModel<F>, usize)>,
+    epochs_since_best: usize,
+    error: Option<Error>,
+}
+
+impl<'a, F: Float> EarlyStopping<'a, F> {
+    pub fn new(rule: StoppingRule<'a, F>) -> Self {
+        Self {
+            rule,
+            epochs: 0,
+            best_accuracy: None,
+            best: None,
+            epochs_since_best: 0,
+            error: None,
+        }
+    }
+
+    /// Number of epochs completed so far.
+    pub fn epochs(&self) -> usize {
+        self.epochs
+    }
+
+    /// The best validation accuracy seen so far, if the rule uses one.
+    pub fn best_accuracy(&self) -> Option<F> {
+        self.best_accuracy
+    }
+
+    /// The epoch with the best validation accuracy so far, if the rule uses
+    /// one.
+    pub fn best_epoch(&self) -> Option<usize> {
+        self.best.as_ref().map(|(_, epoch)| *epoch)
+    }
+
+    /// Returns the error that ended training, if evaluating the validation
+    /// set failed.
+    pub fn into_error(self) -> Option<Error> {
+        self.error
+    }
+
+    /// Returns the parameters with the best validation accuracy and the
+    /// epoch they were reached at, `None` if the rule does not use a
+    /// validation set, or the error that ended training.
+    pub fn into_best(self) -> Result<Option<(LinearModel<F>, usize)>> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.best),
+        }
+    }
+}
+
+impl<F: Float> Observer<F> for EarlyStopping<'_, F> {
+    fn on_epoch(&mut self, epoch: &EpochInfo<'_, F>) -> ControlFlow<()> {
+        self.epochs = epoch.epoch;
+        match self.rule {
+            StoppingRule::Fixed => ControlFlow::Continue(()),
+            StoppingRule::Converged if epoch.mistakes == 0 => ControlFlow::Break(()),
+            StoppingRule::Converged => ControlFlow::Continue(()),
+            StoppingRule::Patience {
+                feature_matrix,
+                labels,
+                patience,
+            } => {
+                let accuracy = match classify(feature_matrix, epoch.theta, epoch.theta_0)
+                    .and_then(|preds| accuracy(&preds, labels))
+                {
+                    Ok(accuracy) => accuracy,
+                    Err(error) => {
+                        self.error = Some(error);
+                        return ControlFlow::Break(());
+                    }
+                };
+                if self.best_accuracy.is_none_or(|best| accuracy > best) {
+                    self.best_accuracy = Some(accuracy);
+                    self.best = Some((
+                        LinearModel::new(epoch.theta.to_vec(), epoch.theta_0),
+                        epoch.epoch,
+                    ));
+                    self.epochs_since_best = 0;
+                } else {
+                    self.epochs_since_best += 1;
+                }
+                if self.epochs_since_best >= patience {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::fs;
+
+use crate::classifier::for_algorithm;
+use crate::error::{Error, Result};
+use crate::evaluation::classifier_accuracy;
+use crate::matrix::FeatureMatrix;
+use crate::model::Algorithm;
+use crate::{DType, Float};
+
+/// Accuracies obtained with one hyperparameter setting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TuningResult<F = DType> {
+    /// Number of iterations through the training data.
+    pub t: usize,
+    /// Regularization parameter, only used by Pegasos.
+    pub lambda: Option<F>,
+    /// Fraction of the final steps averaged, only used by averaged Pegasos.
+    pub tail: Option<F>,
+    pub train_accuracy: F,
+    pub val_accuracy: F,
+}
+
+/// Results of a grid search for one algorithm, in the order they were run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningReport<F = DType> {
+    pub algorithm: Algorithm,
+    pub results: Vec<TuningResult<F>>,
+}
+
+impl<F: Float> TuningReport<F> {
+    /// Returns the setting with the highest validation accuracy. Ties go to
+    /// the setting that was run first.
+    pub fn best(&self) -> Option<&TuningResult<F>> {
+        self.results.iter().fold(None, |best, result| match best {
+            Some(best) if best.val_accuracy >= result.val_accuracy => Some(best),
+            _ => Some(result),
+        })
+    }
+
+    /// Formats the results as a tab-separated table with a header line.
+    /// Accuracies are written with four decimals so reports of different runs
+    /// can be diffed.
+    pub fn to_tsv(&self) -> String {
+        let mut tsv = String::from("algorithm\tt\tlambda\ttail\ttrain_accuracy\tval_accuracy\n");
+        for result in &self.results {
+            let lambda = result.lambda.map(|l| l.to_string()).unwrap_or_default();
+            let tail = result.tail.map(|t| t.to_string()).unwrap_or_default();
+            tsv.push_str(&format!(
+                "{}\t{}\t{lambda}\t{tail}\t{:.4}\t{:.4}\n",
+                self.algorithm, result.t, result.train_accuracy, result.val_accuracy
+            ));
+        }
+        tsv
+    }
+
+    /// Writes the table of [`TuningReport::to_tsv`] to `path`.
+    pub fn write_tsv(&self, path: &str) -> Result<()> {
+        Ok(fs::write(path, self.to_tsv())?)
+    }
+}
+
+/// The hyperparameter settings a grid search tries for one algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningGrid<F = DType> {
+    pub algorithm: Algorithm,
+    /// The values of `t` to try.
+    pub ts: Vec<usize>,
+    /// The values of `lambda` to try, ignored unless
+    /// [`Algorithm::uses_lambda`].
+    pub lambdas: Vec<F>,
+    /// Fraction of the final steps averaged, ignored unless
+    /// [`Algorithm::uses_tail`].
+    pub tail: F,
+}
+
+/// Evaluates the classifier of `grid.algorithm` for every combination of
+/// `t` in `grid.ts` and, for the algorithms that use it, `lambda` in
+/// `grid.lambdas`, iterating over `lambdas` in the inner loop.
+///
+/// Args:
+/// * `grid` - the settings to try.
+/// * `train_feature_matrix` - matrix of the training data.
+/// * `val_feature_matrix` - matrix of the validation data.
+/// * `train_labels` - correct classification of each training data point.
+/// * `val_labels` - correct classification of each validation data point.
+///
+/// Returns: the train and validation accuracy of each setting, or the first
+///     error raised while training or evaluating one of them.
+pub fn tune<F: Float>(
+    grid: &TuningGrid<F>,
+    train_feature_matrix: &FeatureMatrix<F>,
+    val_feature_matrix: &FeatureMatrix<F>,
+    train_labels: &[F],
+    val_labels: &[F],
+) -> Result<TuningReport<F>> {
+    let algorithm = grid.algorithm;
+    let lambdas: Vec<Option<F>> = if algorithm.uses_lambda() {
+        grid.lambdas.iter().copied().map(Some).collect()
+    } else {
+        vec![None]
+    };
+    let tail = algorithm.uses_tail().then_some(grid.tail);
+    let results = grid
+        .ts
+        .iter()
+        .flat_map(|&t| lambdas.iter().map(move |&lambda| (t, lambda)))
+        .map(|(t, lambda)| {
+            let mut classifier = for_algorithm(algorithm, t, lambda.unwrap_or(F::ZERO), grid.tail);
+            let fit = |feature_matrix: &FeatureMatrix<F>, labels: &[F]| {
+                classifier.fit(feature_matrix, labels)?;
+                let model = classifier.model().ok_or(Error::NotFitted)?;
+                Ok((model.theta.clone(), model.theta_0))
+            };
+            let (train_accuracy, val_accuracy) = classifier_accuracy(
+                fit,
+                train_feature_matrix,
+                val_feature_matrix,
+                train_labels,
+                val_labels,
+            )?;
+            Ok(TuningResult {
+                t,
+                lambda,
+                tail,
+                train_accuracy,
+                val_accuracy,
+            })
+        })
+        .collect::<Result<Vec<TuningResult<F>>>>()?;
+    Ok(TuningReport { algorithm, results })
+}
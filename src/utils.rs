@@ -1,28 +1,33 @@
+use automatic_review_analyzer::error::{Error, Result};
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::DType;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use automatic_review_analyzer::DType;
 
-fn parse_str(s: &str) -> DType { s.parse::<DType>().unwrap() }
+fn parse_str(line: usize, s: &str) -> Result<DType> {
+    s.parse::<DType>().map_err(|_| Error::Parse {
+        line,
+        message: format!("invalid number {s:?}"),
+    })
+}
 
-pub fn load_toy_data(path_toy_data: &str) -> (Vec<Vec<DType>>, Vec<DType>) {
-    let file = File::open(path_toy_data).unwrap();
+pub fn load_toy_data(path_toy_data: &str) -> Result<(FeatureMatrix, Vec<DType>)> {
+    let file = File::open(path_toy_data)?;
     let reader = BufReader::new(file);
-    let mut features = Vec::new();
+    let mut features = FeatureMatrix::zeros(0, 2);
     let mut labels = Vec::new();
 
-    for line in reader.lines() {
-        match line {
-            Ok(line) => {
-                let vec: Vec<&str> = line.split('\t').collect();
-                assert_eq!(vec.len(), 3);
-                features.push(vec![parse_str(vec[1]), parse_str(vec[2])]);
-                labels.push(parse_str(vec[0]));
-            }
-            err=> {
-                panic!("{:?}", err)
-            }
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let vec: Vec<&str> = line.split('\t').collect();
+        if vec.len() != 3 {
+            return Err(Error::Parse {
+                line: i + 1,
+                message: format!("expected 3 fields, found {}", vec.len()),
+            });
         }
-
+        features.push_row(&[parse_str(i + 1, vec[1])?, parse_str(i + 1, vec[2])?])?;
+        labels.push(parse_str(i + 1, vec[0])?);
     }
-    (features, labels)
-}
\ No newline at end of file
+    Ok((features, labels))
+}
@@ -0,0 +1,226 @@
+mod common;
+
+use automatic_review_analyzer::checkpoint::{resume, train_with_checkpoints, Checkpoint};
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::{Algorithm, LinearModel};
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::{
+    average_pegasos_with_options, average_perceptron_with_options, pegasos_with_options,
+    perceptron_with_options, DType,
+};
+
+use common::{feature_matrix, LABELS, LAMBDA, ROWS};
+
+const ORDER: [usize; 6] = [4, 0, 3, 5, 1, 2];
+const TAIL: DType = 0.5;
+
+fn in_order() -> TrainOptions<'static> {
+    TrainOptions {
+        order: Some(&ORDER),
+        ..Default::default()
+    }
+}
+
+fn checkpoint(algorithm: Algorithm, t: usize) -> Checkpoint {
+    Checkpoint::new(
+        algorithm,
+        t,
+        LAMBDA,
+        TAIL,
+        &feature_matrix(),
+        &LABELS,
+        ORDER.to_vec(),
+    )
+    .unwrap()
+}
+
+fn temp_path(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("automatic_review_analyzer_{name}.ckpt"));
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn uninterrupted_run_matches_training_functions() {
+    let feature_matrix = feature_matrix();
+    let expected = [
+        perceptron_with_options(&feature_matrix, &LABELS, 5, in_order()).unwrap(),
+        average_perceptron_with_options(&feature_matrix, &LABELS, 5, in_order()).unwrap(),
+        pegasos_with_options(&feature_matrix, &LABELS, 5, LAMBDA, in_order()).unwrap(),
+        average_pegasos_with_options(&feature_matrix, &LABELS, 5, LAMBDA, TAIL, in_order())
+            .unwrap(),
+    ];
+    for (algorithm, expected) in Algorithm::ALL.into_iter().zip(expected) {
+        let mut checkpoint = checkpoint(algorithm, 5);
+        while !checkpoint.is_finished() {
+            checkpoint.run_epoch(&feature_matrix, &LABELS).unwrap();
+        }
+        assert_eq!(checkpoint.epoch(), 5);
+        assert_eq!(
+            checkpoint.model(),
+            LinearModel::from(expected),
+            "{algorithm}"
+        );
+    }
+}
+
+#[test]
+fn resumed_run_matches_uninterrupted_run() {
+    let feature_matrix = feature_matrix();
+    for algorithm in Algorithm::ALL {
+        let path = temp_path(&format!("resume_{algorithm}"));
+        let uninterrupted =
+            train_with_checkpoints(&feature_matrix, &LABELS, checkpoint(algorithm, 7), &path, 2)
+                .unwrap();
+
+        let mut interrupted = checkpoint(algorithm, 7);
+        for _ in 0..3 {
+            interrupted.run_epoch(&feature_matrix, &LABELS).unwrap();
+        }
+        interrupted.save(&path).unwrap();
+        let resumed = resume(&feature_matrix, &LABELS, &path, 2).unwrap();
+        let finished = Checkpoint::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed, uninterrupted, "{algorithm}");
+        assert!(finished.is_finished());
+        assert_eq!(finished.model(), LinearModel::from(resumed));
+    }
+}
+
+#[test]
+fn round_trip_is_exact() {
+    let feature_matrix = feature_matrix();
+    for algorithm in Algorithm::ALL {
+        let mut checkpoint = checkpoint(algorithm, 4);
+        checkpoint.run_epoch(&feature_matrix, &LABELS).unwrap();
+        let text = checkpoint.to_text();
+        let parsed = Checkpoint::parse(&text).unwrap();
+        assert_eq!(parsed.to_text(), text);
+        assert_eq!(parsed.algorithm(), algorithm);
+        assert_eq!(parsed.t(), 4);
+        assert_eq!(parsed.epoch(), 1);
+        assert_eq!(parsed.lambda(), algorithm.uses_lambda().then_some(LAMBDA));
+        assert_eq!(parsed.tail(), algorithm.uses_tail().then_some(TAIL));
+    }
+}
+
+#[test]
+fn to_text_layout() {
+    let mut checkpoint = checkpoint(Algorithm::AveragePegasos, 5);
+    checkpoint.run_epoch(&feature_matrix(), &LABELS).unwrap();
+    let text = checkpoint.to_text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "automatic_review_analyzer checkpoint 1");
+    assert_eq!(lines[2], "tail\t0.5");
+    assert_eq!(lines[3], "epoch\t1");
+    assert!(lines[5].starts_with("data\t"));
+    assert_eq!(&lines[6..12], ["4", "0", "3", "5", "1", "2"]);
+    assert_eq!(lines[12], "algorithm\taverage_pegasos");
+    assert_eq!(lines[14], "steps\t6");
+    assert_eq!(lines[15], "average_after\t15");
+    assert_eq!(lines[16], "averaged\t0");
+    assert_eq!(lines.len(), 23);
+    assert!(lines[20..].iter().all(|line| line.split('\t').count() == 2));
+}
+
+#[test]
+fn invalid_settings_and_data_fail() {
+    let feature_matrix = feature_matrix();
+    assert!(matches!(
+        Checkpoint::new(
+            Algorithm::AveragePegasos,
+            3,
+            LAMBDA,
+            0.,
+            &feature_matrix,
+            &LABELS,
+            ORDER.to_vec()
+        ),
+        Err(Error::InvalidParameter { name: "tail", .. })
+    ));
+    assert!(matches!(
+        Checkpoint::new(
+            Algorithm::Perceptron,
+            3,
+            LAMBDA,
+            TAIL,
+            &feature_matrix,
+            &LABELS,
+            vec![0, 0, 1, 2, 3, 4]
+        ),
+        Err(Error::InvalidParameter { name: "order", .. })
+    ));
+
+    let path = temp_path("invalid");
+    let result = train_with_checkpoints(
+        &feature_matrix,
+        &LABELS,
+        checkpoint(Algorithm::Pegasos, 3),
+        &path,
+        0,
+    );
+    assert!(matches!(
+        result,
+        Err(Error::InvalidParameter { name: "every", .. })
+    ));
+
+    let mut checkpoint = checkpoint(Algorithm::Pegasos, 3);
+    let fewer_rows = FeatureMatrix::from_rows(&ROWS[..5]).unwrap();
+    assert!(matches!(
+        checkpoint.run_epoch(&fewer_rows, &LABELS[..5]),
+        Err(Error::DimensionMismatch {
+            what: "number of samples",
+            ..
+        })
+    ));
+    assert_eq!(checkpoint.epoch(), 0);
+}
+
+#[test]
+fn resuming_on_other_data_fails() {
+    let path = temp_path("other_data");
+    let mut checkpoint = checkpoint(Algorithm::Perceptron, 3);
+    checkpoint.run_epoch(&feature_matrix(), &LABELS).unwrap();
+    checkpoint.save(&path).unwrap();
+
+    let mut labels = LABELS;
+    labels[2] = 1.;
+    let result = resume(&feature_matrix(), &labels, &path, 1);
+    let unchanged: Checkpoint = Checkpoint::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(
+        result,
+        Err(Error::InvalidParameter {
+            name: "checkpoint",
+            ..
+        })
+    ));
+    assert_eq!(unchanged.epoch(), 1);
+}
+
+#[test]
+fn parse_rejects_invalid_files() {
+    let mut checkpoint = checkpoint(Algorithm::AveragePerceptron, 3);
+    checkpoint.run_epoch(&feature_matrix(), &LABELS).unwrap();
+    let text = checkpoint.to_text();
+
+    let cases = [
+        text.replace("checkpoint 1", "checkpoint 2"),
+        text.replace("data\t", "data\tz"),
+        text.replace("epoch\t1", "epoch\tone"),
+        text.replacen("\n4\n", "\n0\n", 1),
+        text.lines().take(20).collect::<Vec<_>>().join("\n"),
+        text.replacen("\t", "@", 13),
+        format!("{text}extra\n"),
+        text.replace("n_sample\t6", &format!("n_sample\t{}", usize::MAX)),
+        text.replace("n_feature\t3", &format!("n_feature\t{}", usize::MAX)),
+    ];
+    for case in cases {
+        assert!(
+            matches!(Checkpoint::<DType>::parse(&case), Err(Error::Parse { .. })),
+            "{case}"
+        );
+    }
+}
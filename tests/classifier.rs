@@ -0,0 +1,113 @@
+mod common;
+
+use automatic_review_analyzer::classifier::{
+    for_algorithm, AveragePegasos, AveragePerceptron, Classifier, Pegasos, Perceptron,
+};
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::model::{Algorithm, LinearModel};
+use automatic_review_analyzer::{
+    average_pegasos, average_perceptron, classify, pegasos, perceptron, DType,
+};
+
+use common::separable_data;
+
+#[test]
+fn classifiers_match_training_functions() {
+    let (feature_matrix, labels) = separable_data();
+    let expected = [
+        perceptron(&feature_matrix, &labels, 5).unwrap(),
+        average_perceptron(&feature_matrix, &labels, 5).unwrap(),
+        pegasos(&feature_matrix, &labels, 5, 0.1).unwrap(),
+        average_pegasos(&feature_matrix, &labels, 5, 0.1, 0.5).unwrap(),
+    ];
+    let mut classifiers: [Box<dyn Classifier>; 4] = [
+        Box::new(Perceptron::new(5)),
+        Box::new(AveragePerceptron::new(5)),
+        Box::new(Pegasos::new(5, 0.1)),
+        Box::new(AveragePegasos::new(5, 0.1, 0.5)),
+    ];
+
+    for (classifier, (algorithm, thetas)) in classifiers
+        .iter_mut()
+        .zip(Algorithm::ALL.into_iter().zip(expected))
+    {
+        assert_eq!(classifier.algorithm(), algorithm);
+        classifier.fit(&feature_matrix, &labels).unwrap();
+        assert_eq!(classifier.model(), Some(&LinearModel::from(thetas.clone())));
+        assert_eq!(
+            classifier.predict(&feature_matrix).unwrap(),
+            classify(&feature_matrix, &thetas.0, thetas.1).unwrap()
+        );
+    }
+}
+
+#[test]
+fn decision_function_of_fitted_classifier() {
+    let (feature_matrix, labels) = separable_data();
+    let mut classifier = Perceptron::new(5);
+    classifier.fit(&feature_matrix, &labels).unwrap();
+    let model = classifier.model().unwrap();
+    let expected: Vec<DType> = feature_matrix
+        .rows()
+        .map(|row| model.decision_function(row))
+        .collect();
+    assert_eq!(
+        classifier.decision_function(&feature_matrix).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn for_algorithm_builds_the_requested_classifier() {
+    let (feature_matrix, labels) = separable_data();
+    for algorithm in Algorithm::ALL {
+        let mut classifier = for_algorithm(algorithm, 3, 0.1, 1.);
+        assert_eq!(classifier.algorithm(), algorithm);
+        classifier.fit(&feature_matrix, &labels).unwrap();
+        assert_eq!(classifier.predict(&feature_matrix).unwrap(), labels);
+    }
+}
+
+#[test]
+fn unfitted_classifier_fails() {
+    let (feature_matrix, _) = separable_data();
+    let classifier = Pegasos::new(5, 0.1);
+    assert!(classifier.model().is_none());
+    assert!(matches!(
+        classifier.predict(&feature_matrix),
+        Err(Error::NotFitted)
+    ));
+    assert!(matches!(
+        classifier.decision_function(&feature_matrix),
+        Err(Error::NotFitted)
+    ));
+}
+
+#[test]
+fn failed_fit_reports_training_error() {
+    let (feature_matrix, labels) = separable_data();
+    let mut classifier = Pegasos::new(5, -1.);
+    assert!(matches!(
+        classifier.fit(&feature_matrix, &labels),
+        Err(Error::InvalidParameter { name: "lambda", .. })
+    ));
+}
+
+#[test]
+fn failed_fit_discards_earlier_fit() {
+    let (feature_matrix, labels) = separable_data();
+    for algorithm in Algorithm::ALL {
+        let mut classifier = for_algorithm(algorithm, 3, 0.1, 1.);
+        classifier.fit(&feature_matrix, &labels).unwrap();
+        assert!(classifier.model().is_some());
+        assert!(matches!(
+            classifier.fit(&feature_matrix, &labels[..3]),
+            Err(Error::DimensionMismatch { .. })
+        ));
+        assert!(classifier.model().is_none(), "{algorithm}");
+        assert!(matches!(
+            classifier.predict(&feature_matrix),
+            Err(Error::NotFitted)
+        ));
+    }
+}
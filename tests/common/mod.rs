@@ -0,0 +1,28 @@
+//! Data shared by the integration tests. Each test crate includes this
+//! module and uses only some of it.
+#![allow(dead_code)]
+
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::DType;
+
+pub const ROWS: [[DType; 3]; 6] = [
+    [2., 1., 0.],
+    [1., 3., -1.],
+    [-1., -2., 0.5],
+    [-3., -1., 1.],
+    [0.5, 0.5, 2.],
+    [-0.5, 1., -2.],
+];
+pub const LABELS: [DType; 6] = [1., 1., -1., -1., -1., 1.];
+pub const LAMBDA: DType = 0.1;
+
+pub fn feature_matrix() -> FeatureMatrix {
+    FeatureMatrix::from_rows(&ROWS).unwrap()
+}
+
+/// Four samples with two features, separated by the line `x + y = 0`.
+pub fn separable_data() -> (FeatureMatrix, Vec<DType>) {
+    let feature_matrix =
+        FeatureMatrix::from_rows(&[[2., 1.], [1., 3.], [-1., -2.], [-3., -1.]]).unwrap();
+    (feature_matrix, vec![1., 1., -1., -1.])
+}
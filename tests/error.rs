@@ -0,0 +1,123 @@
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::LinearModel;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::reviews::{labels, parse_reviews};
+use automatic_review_analyzer::sparse::{pegasos_sparse, SparseMatrix};
+use automatic_review_analyzer::{
+    average_perceptron, hinge_loss_full, pegasos, perceptron, perceptron_with_options,
+};
+
+fn feature_matrix() -> FeatureMatrix {
+    FeatureMatrix::from_rows(&[[1., 2.], [-1., 0.]]).unwrap()
+}
+
+#[test]
+fn labels_length_mismatch() {
+    assert!(matches!(
+        perceptron(&feature_matrix(), &[1.], 1),
+        Err(Error::DimensionMismatch {
+            expected: 2,
+            found: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        hinge_loss_full(&feature_matrix(), &[1., 1.], &[1.], 0.),
+        Err(Error::DimensionMismatch {
+            expected: 2,
+            found: 1,
+            ..
+        })
+    ));
+}
+#[test]
+fn labels_must_be_plus_minus_one() {
+    assert!(matches!(
+        average_perceptron(&feature_matrix(), &[1., 0.], 1),
+        Err(Error::InvalidLabel { index: 1, .. })
+    ));
+    assert!(matches!(
+        pegasos_sparse(
+            &SparseMatrix::from_dense(&feature_matrix()),
+            &[2., 1.],
+            1,
+            0.1
+        ),
+        Err(Error::InvalidLabel { index: 0, .. })
+    ));
+}
+#[test]
+fn non_finite_features() {
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.], [f32::NAN, 0.]]).unwrap();
+
+    assert!(matches!(
+        pegasos(&feature_matrix, &[1., -1.], 1, 0.1),
+        Err(Error::NonFiniteValue { row: 1, col: 0, .. })
+    ));
+}
+#[test]
+fn invalid_parameters() {
+    assert!(matches!(
+        pegasos(&feature_matrix(), &[1., -1.], 1, f32::NAN),
+        Err(Error::InvalidParameter { name: "lambda", .. })
+    ));
+    assert!(matches!(
+        average_perceptron(&feature_matrix(), &[1., -1.], 0),
+        Err(Error::InvalidParameter { name: "t", .. })
+    ));
+    assert!(matches!(
+        perceptron_with_options(
+            &feature_matrix(),
+            &[1., -1.],
+            1,
+            TrainOptions {
+                order: Some(&[0, 0]),
+                ..Default::default()
+            }
+        ),
+        Err(Error::InvalidParameter { name: "order", .. })
+    ));
+    assert!(matches!(
+        perceptron_with_options(
+            &feature_matrix(),
+            &[1., -1.],
+            1,
+            TrainOptions {
+                order: Some(&[0]),
+                ..Default::default()
+            }
+        ),
+        Err(Error::DimensionMismatch { .. })
+    ));
+}
+#[test]
+fn predict_batch_feature_mismatch() {
+    let model = LinearModel::new(vec![1., 2., 3.], 0.);
+
+    assert!(matches!(
+        model.predict_batch(&feature_matrix()),
+        Err(Error::DimensionMismatch {
+            expected: 3,
+            found: 2,
+            ..
+        })
+    ));
+}
+#[test]
+fn malformed_reviews_report_line() {
+    let contents = "sentiment\tproductId\tuserId\tsummary\ttext\thelpfulY\thelpfulN\n\
+                    1\tB0\tU0\tok\tfine\t0\t0\n\
+                    1\tB1\tU1\tok\tfine\tmany\t0\n";
+
+    let err = parse_reviews(contents).unwrap_err();
+    assert!(matches!(err, Error::Parse { line: 3, .. }), "{err}");
+    assert_eq!(err.to_string(), "line 3: invalid helpfulY count \"many\"");
+
+    let reviews =
+        parse_reviews(&contents.replace("many", "0").replace("\n1\tB0", "\n\tB0")).unwrap();
+    assert!(matches!(
+        labels(&reviews),
+        Err(Error::MissingLabel { index: 0 })
+    ));
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::evaluation::{accuracy, classifier_accuracy, ConfusionMatrix};
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::{average_perceptron, pegasos, perceptron};
+
+use common::separable_data;
+
+#[test]
+fn accuracy_fraction_of_matches() {
+    assert_eq!(
+        accuracy(&[1., -1., 1., 1.], &[1., 1., 1., -1.]).unwrap(),
+        0.5
+    );
+    assert_eq!(accuracy(&[-1., -1.], &[-1., -1.]).unwrap(), 1.);
+}
+#[test]
+fn accuracy_invalid_input() {
+    assert!(matches!(
+        accuracy(&[1., -1.], &[1.]),
+        Err(Error::DimensionMismatch {
+            expected: 2,
+            found: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        accuracy::<f32>(&[], &[]),
+        Err(Error::EmptyDataset)
+    ));
+}
+#[test]
+fn classifier_accuracy_all_algorithms() {
+    let (train_feature_matrix, train_labels) = separable_data();
+    let val_feature_matrix = FeatureMatrix::from_rows(&[[1., 1.], [-2., -2.], [-1., 1.5]]).unwrap();
+    let val_labels = [1., -1., -1.];
+
+    for (train_accuracy, val_accuracy) in [
+        classifier_accuracy(
+            |m, l| perceptron(m, l, 5),
+            &train_feature_matrix,
+            &val_feature_matrix,
+            &train_labels,
+            &val_labels,
+        )
+        .unwrap(),
+        classifier_accuracy(
+            |m, l| average_perceptron(m, l, 5),
+            &train_feature_matrix,
+            &val_feature_matrix,
+            &train_labels,
+            &val_labels,
+        )
+        .unwrap(),
+        classifier_accuracy(
+            |m, l| pegasos(m, l, 5, 0.1),
+            &train_feature_matrix,
+            &val_feature_matrix,
+            &train_labels,
+            &val_labels,
+        )
+        .unwrap(),
+    ] {
+        assert_eq!(train_accuracy, 1.);
+        assert!(val_accuracy >= 2. / 3., "{val_accuracy}");
+    }
+}
+#[test]
+fn confusion_matrix_metrics() {
+    let matrix =
+        ConfusionMatrix::from_predictions(&[1., 1., -1., -1., 1.], &[1., -1., -1., 1., 1.])
+            .unwrap();
+
+    assert_eq!(
+        matrix,
+        ConfusionMatrix {
+            true_positive: 2,
+            false_positive: 1,
+            true_negative: 1,
+            false_negative: 1,
+        }
+    );
+    assert_eq!(matrix.total(), 5);
+    assert_eq!(matrix.accuracy(), 0.6);
+    assert_eq!(matrix.precision(), 2. / 3.);
+    assert_eq!(matrix.recall(), 2. / 3.);
+    assert_eq!(matrix.f1(), 2. / 3.);
+}
+#[test]
+fn confusion_matrix_without_positives() {
+    let matrix = ConfusionMatrix::from_predictions(&[-1., -1.], &[-1., -1.]).unwrap();
+
+    assert_eq!(matrix.accuracy(), 1.);
+    assert_eq!(matrix.precision(), 0.);
+    assert_eq!(matrix.recall(), 0.);
+    assert_eq!(matrix.f1(), 0.);
+    assert!(matches!(
+        ConfusionMatrix::from_predictions(&[1., 0.], &[1., 1.]),
+        Err(Error::InvalidLabel { index: 1, .. })
+    ));
+    assert!(matches!(
+        ConfusionMatrix::from_predictions::<f32>(&[], &[]),
+        Err(Error::EmptyDataset)
+    ));
+}
@@ -0,0 +1,56 @@
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::explain::{most_explanatory_words, WordWeight};
+use automatic_review_analyzer::features::{bag_of_words, extract_bow_feature_vectors, Dictionary};
+use automatic_review_analyzer::pegasos;
+
+fn word_weights(word_weights: &[WordWeight]) -> Vec<(&str, f32)> {
+    word_weights
+        .iter()
+        .map(|w| (w.word.as_str(), w.weight))
+        .collect()
+}
+
+#[test]
+fn top_k_of_each_sign() {
+    let dictionary = Dictionary::from_words(["a", "b", "c", "d", "e", "f"]);
+    let theta = [0.5, -2., 0., 3., -0.1, 0.5];
+
+    let explanation = most_explanatory_words(&theta, &dictionary, 2).unwrap();
+    assert_eq!(word_weights(&explanation.positive), [("d", 3.), ("a", 0.5)]);
+    assert_eq!(
+        word_weights(&explanation.negative),
+        [("b", -2.), ("e", -0.1)]
+    );
+
+    let explanation = most_explanatory_words(&theta, &dictionary, 10).unwrap();
+    assert_eq!(
+        word_weights(&explanation.positive),
+        [("d", 3.), ("a", 0.5), ("f", 0.5)]
+    );
+    assert_eq!(explanation.negative.len(), 2);
+    assert!(most_explanatory_words(&theta, &dictionary, 0)
+        .unwrap()
+        .positive
+        .is_empty());
+}
+#[test]
+fn vocabulary_must_match_theta() {
+    let dictionary = Dictionary::from_words(["a"]);
+
+    assert!(matches!(
+        most_explanatory_words(&[1., 2.], &dictionary, 1),
+        Err(Error::DimensionMismatch { .. })
+    ));
+}
+#[test]
+fn explain_trained_model() {
+    let texts = ["great taste", "awful taste", "great price", "awful smell"];
+    let labels = [1., -1., 1., -1.];
+    let dictionary = bag_of_words(&texts, None);
+    let feature_matrix = extract_bow_feature_vectors(&texts, &dictionary);
+    let (theta, _) = pegasos(&feature_matrix, &labels, 10, 0.01).unwrap();
+
+    let explanation = most_explanatory_words(&theta, &dictionary, 1).unwrap();
+    assert_eq!(explanation.positive[0].word, "great");
+    assert_eq!(explanation.negative[0].word, "awful");
+}
@@ -0,0 +1,67 @@
+use automatic_review_analyzer::features::{
+    bag_of_words, extract_bow_feature_vectors, extract_words, Dictionary, Stopwords,
+};
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::LinearModel;
+use automatic_review_analyzer::perceptron;
+
+#[test]
+fn extract_words_splits_punctuation_and_digits() {
+    assert_eq!(
+        extract_words("YUMMY! It's 20oz, great."),
+        ["yummy", "!", "it", "'", "s", "2", "0", "oz", ",", "great", "."]
+    );
+}
+#[test]
+fn bag_of_words_first_appearance_order() {
+    let dictionary = bag_of_words(&["Mary loves apples", "Red apples"], None);
+
+    assert_eq!(dictionary.words(), ["mary", "loves", "apples", "red"]);
+    assert_eq!(dictionary.index("apples"), Some(2));
+    assert_eq!(dictionary.index("pears"), None);
+    assert_eq!(dictionary.word(3), Some("red"));
+}
+#[test]
+fn bag_of_words_skips_stopwords() {
+    let stopwords = Stopwords::parse("the\r\nAnd\r\n\r\n");
+    let dictionary = bag_of_words(&["The candy and the chips"], Some(&stopwords));
+
+    assert_eq!(stopwords.len(), 2);
+    assert_eq!(dictionary.words(), ["candy", "chips"]);
+}
+#[test]
+fn stopwords_load_data_file() {
+    let stopwords = Stopwords::load("data/stopwords.txt").unwrap();
+
+    assert_eq!(stopwords.len(), 127);
+    assert!(stopwords.contains("myself"));
+    assert!(!stopwords.contains("candy"));
+}
+#[test]
+fn extract_bow_feature_vectors_binary() {
+    let dictionary = Dictionary::from_words(["mary", "loves", "apples", "red"]);
+    let feature_matrix = extract_bow_feature_vectors(
+        &["Mary loves apples apples", "Red apples and pears"],
+        &dictionary,
+    );
+
+    assert_eq!(
+        feature_matrix,
+        FeatureMatrix::from_rows(&[[1., 1., 1., 0.], [0., 0., 1., 1.]]).unwrap()
+    );
+}
+#[test]
+fn bow_features_train_perceptron() {
+    let texts = [
+        "great tasty snack",
+        "awful stale chips",
+        "tasty chips",
+        "stale snack",
+    ];
+    let labels = [1., -1., 1., -1.];
+    let dictionary = bag_of_words(&texts, None);
+    let feature_matrix = extract_bow_feature_vectors(&texts, &dictionary);
+
+    let model = LinearModel::from(perceptron(&feature_matrix, &labels, 5).unwrap());
+    assert_eq!(model.predict_batch(&feature_matrix).unwrap(), labels);
+}
@@ -0,0 +1,97 @@
+use automatic_review_analyzer::checkpoint::{resume, Checkpoint};
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::{Algorithm, LinearModel};
+use automatic_review_analyzer::order::get_order;
+use automatic_review_analyzer::persist::SavedModel;
+use automatic_review_analyzer::sparse::{pegasos_sparse, SparseMatrix};
+use automatic_review_analyzer::{average_perceptron, hinge_loss_full, pegasos, DType, Float};
+
+const ROWS: [[f64; 3]; 4] = [
+    [1., 0.5, -2.],
+    [-1.5, 2., 0.25],
+    [0.75, -1., 1.],
+    [-0.5, -0.25, -1.],
+];
+const LABELS: [f64; 4] = [1., -1., 1., -1.];
+
+fn to_f32(values: &[f64]) -> Vec<f32> {
+    values.iter().map(|&x| x as f32).collect()
+}
+
+#[test]
+fn f32_and_f64_agree() {
+    let feature_matrix_64 = FeatureMatrix::from_rows(&ROWS).unwrap();
+    let rows_32: Vec<Vec<f32>> = ROWS.iter().map(|row| to_f32(row)).collect();
+    let feature_matrix_32: FeatureMatrix = FeatureMatrix::from_rows(&rows_32).unwrap();
+    let labels_32 = to_f32(&LABELS);
+
+    let (theta_64, theta_0_64) = pegasos(&feature_matrix_64, &LABELS, 10, 0.1).unwrap();
+    let (theta_32, theta_0_32) = pegasos(&feature_matrix_32, &labels_32, 10, 0.1).unwrap();
+    for (&a, &b) in theta_64.iter().zip(&theta_32) {
+        assert!((a - b as f64).abs() < 1e-5, "{a} != {b}");
+    }
+    assert!((theta_0_64 - theta_0_32 as f64).abs() < 1e-5);
+
+    let (theta_64, theta_0_64) = average_perceptron(&feature_matrix_64, &LABELS, 5).unwrap();
+    let (theta_32, theta_0_32) = average_perceptron(&feature_matrix_32, &labels_32, 5).unwrap();
+    for (&a, &b) in theta_64.iter().zip(&theta_32) {
+        assert!((a - b as f64).abs() < 1e-5, "{a} != {b}");
+    }
+    assert!((theta_0_64 - theta_0_32 as f64).abs() < 1e-5);
+}
+#[test]
+fn f64_model_and_sparse_training() {
+    let feature_matrix = FeatureMatrix::from_rows(&ROWS).unwrap();
+    let sparse_matrix = SparseMatrix::from_dense(&feature_matrix);
+
+    let dense = pegasos(&feature_matrix, &LABELS, 5, 0.01).unwrap();
+    let sparse = pegasos_sparse(&sparse_matrix, &LABELS, 5, 0.01).unwrap();
+    for (&a, &b) in dense.0.iter().zip(&sparse.0) {
+        assert!((a - b).abs() < 1e-12, "{a} != {b}");
+    }
+
+    let model: LinearModel<f64> = LinearModel::from(dense);
+    assert_eq!(model.predict_batch(&feature_matrix).unwrap(), LABELS);
+    let loss = hinge_loss_full(&feature_matrix, &LABELS, &model.theta, model.theta_0).unwrap();
+    assert!(loss < 1.);
+}
+#[test]
+fn f64_models_and_checkpoints_round_trip() {
+    let feature_matrix = FeatureMatrix::from_rows(&ROWS).unwrap();
+    let thetas = pegasos(&feature_matrix, &LABELS, 5, 0.01).unwrap();
+
+    let saved = SavedModel::new(
+        Algorithm::Pegasos,
+        5,
+        Some(0.01),
+        None,
+        LinearModel::from(thetas.clone()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(SavedModel::<f64>::parse(&saved.to_text()).unwrap(), saved);
+
+    let path = std::env::temp_dir().join("automatic_review_analyzer_f64.ckpt");
+    let path = path.to_str().unwrap();
+    let mut checkpoint = Checkpoint::new(
+        Algorithm::Pegasos,
+        5,
+        0.01,
+        1.,
+        &feature_matrix,
+        &LABELS,
+        get_order(ROWS.len()),
+    )
+    .unwrap();
+    checkpoint.run_epoch(&feature_matrix, &LABELS).unwrap();
+    checkpoint.save(path).unwrap();
+    let resumed = resume(&feature_matrix, &LABELS, path, 1).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(resumed, thetas);
+}
+#[test]
+fn float_constants() {
+    assert_eq!(<f64 as Float>::from_usize(3).sqrt(), 3f64.sqrt());
+    assert_eq!(<DType as Float>::TOLERANCE, 1e-7);
+    assert_eq!(<f32 as Float>::from_f64(0.1).to_f64(), 0.1f32 as f64);
+}
@@ -1,3 +1,4 @@
+use automatic_review_analyzer::matrix::FeatureMatrix;
 use automatic_review_analyzer::{hinge_loss_full, hinge_loss_single, DType};
 const EPSILON: DType = 1e-6;
 
@@ -217,13 +218,13 @@ fn hinge_loss_single_theta_equal_to_zero_2() {
 
 #[test]
 fn hinge_loss_full_test() {
-    let feature_vector = vec![vec![1., 2.], vec![1., 2.]];
+    let feature_vector = FeatureMatrix::from_rows(&[[1., 2.], [1., 2.]]).unwrap();
     let labels = vec![1., 1.];
     let theta = vec![-1., 1.];
     let theta_0 = -0.2;
     let exp_result = 1.0 - 0.8;
     assert_eq!(
-        hinge_loss_full(&feature_vector, &labels, &theta, theta_0),
+        hinge_loss_full(&feature_vector, &labels, &theta, theta_0).unwrap(),
         exp_result
     );
 }
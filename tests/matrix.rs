@@ -0,0 +1,76 @@
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::{FeatureMatrix, ShapeError};
+use automatic_review_analyzer::{average_perceptron, hinge_loss_full, pegasos, perceptron};
+
+#[test]
+fn from_rows_shape_and_views() {
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2., 3.], [4., 5., 6.]]).unwrap();
+
+    assert_eq!(feature_matrix.shape(), (2, 3));
+    assert_eq!(feature_matrix.row(1), [4., 5., 6.]);
+    assert_eq!(feature_matrix.as_slice(), [1., 2., 3., 4., 5., 6.]);
+    assert_eq!(
+        feature_matrix.rows().collect::<Vec<_>>(),
+        [[1., 2., 3.], [4., 5., 6.]]
+    );
+    assert_eq!(
+        FeatureMatrix::new(vec![1., 2., 3., 4., 5., 6.], 2, 3).unwrap(),
+        feature_matrix
+    );
+}
+#[test]
+fn construction_validates_shape() {
+    assert_eq!(
+        FeatureMatrix::try_from(vec![vec![1., 2.], vec![3.]]),
+        Err(ShapeError::RowLength {
+            row: 1,
+            expected: 2,
+            found: 1
+        })
+    );
+    assert_eq!(
+        FeatureMatrix::new(vec![1., 2., 3.], 2, 2),
+        Err(ShapeError::DataLength {
+            expected: 4,
+            found: 3
+        })
+    );
+
+    let mut feature_matrix = FeatureMatrix::zeros(0, 2);
+    assert!(feature_matrix.push_row(&[1.]).is_err());
+    feature_matrix.push_row(&[1., 2.]).unwrap();
+    feature_matrix.row_mut(0)[1] = 3.;
+    assert_eq!(feature_matrix.row(0), [1., 3.]);
+}
+#[test]
+#[should_panic]
+fn row_out_of_bounds() {
+    FeatureMatrix::<f32>::zeros(2, 2).row(2);
+}
+#[test]
+fn empty_matrix_does_not_panic() {
+    let feature_matrix = FeatureMatrix::zeros(0, 3);
+
+    assert!(feature_matrix.is_empty());
+    assert!(matches!(
+        perceptron(&feature_matrix, &[], 5),
+        Err(Error::EmptyDataset)
+    ));
+    assert!(matches!(
+        pegasos(&feature_matrix, &[], 5, 0.1),
+        Err(Error::EmptyDataset)
+    ));
+    assert!(matches!(
+        average_perceptron(&feature_matrix, &[], 5),
+        Err(Error::EmptyDataset)
+    ));
+}
+#[test]
+fn hinge_loss_full_rows() {
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.], [2., 1.]]).unwrap();
+
+    assert_eq!(
+        hinge_loss_full(&feature_matrix, &[1., -1.], &[1., 0.], 0.).unwrap(),
+        1.5
+    );
+}
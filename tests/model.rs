@@ -0,0 +1,47 @@
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::LinearModel;
+use automatic_review_analyzer::{classify, pegasos};
+
+#[test]
+fn decision_function_and_predict() {
+    let model = LinearModel::new(vec![1., -2.], 0.5);
+
+    assert_eq!(model.n_feature(), 2);
+    assert_eq!(model.decision_function(&[3., 1.]), 1.5);
+    assert_eq!(model.predict(&[3., 1.]), 1.);
+    assert_eq!(model.predict(&[0., 1.]), -1.);
+}
+#[test]
+fn predict_boundary_is_negative() {
+    let model = LinearModel::new(vec![1., 1.], -1.);
+
+    assert_eq!(model.decision_function(&[0.5, 0.5]), 0.);
+    assert_eq!(model.predict(&[0.5, 0.5]), -1.);
+    assert_eq!(LinearModel::zeros(3).predict(&[1., 2., 3.]), -1.);
+}
+#[test]
+fn batch_variants() {
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 1.], [-1., -1.], [0., 0.]]).unwrap();
+    let model = LinearModel::new(vec![2., 1.], 0.);
+
+    assert_eq!(
+        model.decision_function_batch(&feature_matrix).unwrap(),
+        [3., -3., 0.]
+    );
+    assert_eq!(
+        model.predict_batch(&feature_matrix).unwrap(),
+        [1., -1., -1.]
+    );
+    assert_eq!(
+        classify(&feature_matrix, &model.theta, model.theta_0).unwrap(),
+        [1., -1., -1.]
+    );
+}
+#[test]
+fn from_training_result() {
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
+    let model = LinearModel::from(pegasos(&feature_matrix, &[1.], 1, 0.2).unwrap());
+
+    assert_eq!(model, LinearModel::new(vec![1., 2.], 1.));
+    assert_eq!(model.predict_batch(&feature_matrix).unwrap(), [1.]);
+}
@@ -0,0 +1,195 @@
+mod common;
+
+use std::ops::ControlFlow;
+
+use automatic_review_analyzer::observer::{EpochHistory, EpochInfo, Observer, StepInfo};
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::{
+    average_pegasos_with_options, average_perceptron_with_options, pegasos_with_options,
+    perceptron_with_options, DType,
+};
+
+use common::separable_data;
+
+/// Options visiting the samples in `order` and reporting to `observer`.
+fn observed<'a>(order: &'a [usize], observer: &'a mut dyn Observer) -> TrainOptions<'a> {
+    TrainOptions {
+        order: Some(order),
+        observer: Some(observer),
+        ..Default::default()
+    }
+}
+
+fn in_order(order: &[usize]) -> TrainOptions<'_> {
+    TrainOptions {
+        order: Some(order),
+        ..Default::default()
+    }
+}
+
+/// Stops at the end of the first epoch without mistakes.
+#[derive(Default)]
+struct StopWhenSeparated {
+    epochs: usize,
+}
+
+impl Observer for StopWhenSeparated {
+    fn on_epoch(&mut self, epoch: &EpochInfo) -> ControlFlow<()> {
+        self.epochs = epoch.epoch;
+        if epoch.mistakes == 0 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Stops after a fixed number of steps, recording every step it sees.
+struct StopAfter {
+    limit: usize,
+    steps: Vec<(usize, usize, usize)>,
+    epochs: usize,
+}
+
+impl Observer for StopAfter {
+    fn on_step(&mut self, step: &StepInfo) -> ControlFlow<()> {
+        self.steps.push((step.epoch, step.step, step.sample));
+        if step.step == self.limit {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn on_epoch(&mut self, _epoch: &EpochInfo) -> ControlFlow<()> {
+        self.epochs += 1;
+        ControlFlow::Continue(())
+    }
+}
+
+#[test]
+fn epoch_history_records_every_epoch() {
+    let (feature_matrix, labels) = separable_data();
+    let order = [0, 1, 2, 3];
+    let mut history = EpochHistory::default();
+    let result =
+        perceptron_with_options(&feature_matrix, &labels, 5, observed(&order, &mut history))
+            .unwrap();
+
+    assert_eq!(history.mistakes.len(), 5);
+    assert_eq!(history.theta_norms.len(), 5);
+    assert!(history.mistakes[0] > 0);
+    assert_eq!(*history.mistakes.last().unwrap(), 0);
+    let norm = result.0.iter().map(|x| x * x).sum::<DType>().sqrt();
+    assert!((history.theta_norms[4] - norm).abs() < 1e-6);
+}
+
+#[test]
+fn observed_training_matches_unobserved() {
+    let (feature_matrix, labels) = separable_data();
+    let order = [2, 0, 3, 1];
+    assert_eq!(
+        perceptron_with_options(
+            &feature_matrix,
+            &labels,
+            3,
+            observed(&order, &mut EpochHistory::default())
+        )
+        .unwrap(),
+        perceptron_with_options(&feature_matrix, &labels, 3, in_order(&order)).unwrap()
+    );
+    assert_eq!(
+        average_perceptron_with_options(&feature_matrix, &labels, 3, observed(&order, &mut ()))
+            .unwrap(),
+        average_perceptron_with_options(&feature_matrix, &labels, 3, in_order(&order)).unwrap()
+    );
+    assert_eq!(
+        pegasos_with_options(&feature_matrix, &labels, 3, 0.1, observed(&order, &mut ())).unwrap(),
+        pegasos_with_options(&feature_matrix, &labels, 3, 0.1, in_order(&order)).unwrap()
+    );
+    assert_eq!(
+        average_pegasos_with_options(
+            &feature_matrix,
+            &labels,
+            3,
+            0.1,
+            0.5,
+            observed(&order, &mut ())
+        )
+        .unwrap(),
+        average_pegasos_with_options(&feature_matrix, &labels, 3, 0.1, 0.5, in_order(&order))
+            .unwrap()
+    );
+}
+
+#[test]
+fn on_epoch_break_stops_training() {
+    let (feature_matrix, labels) = separable_data();
+    let order = [0, 1, 2, 3];
+    let mut observer = StopWhenSeparated::default();
+    let stopped = perceptron_with_options(
+        &feature_matrix,
+        &labels,
+        100,
+        observed(&order, &mut observer),
+    )
+    .unwrap();
+
+    assert!(observer.epochs < 100);
+    let full = perceptron_with_options(&feature_matrix, &labels, observer.epochs, in_order(&order))
+        .unwrap();
+    assert_eq!(stopped, full);
+}
+
+#[test]
+fn on_step_break_stops_training() {
+    let (feature_matrix, labels) = separable_data();
+    let order = [3, 1, 0, 2];
+    let mut observer = StopAfter {
+        limit: 6,
+        steps: Vec::new(),
+        epochs: 0,
+    };
+    pegasos_with_options(
+        &feature_matrix,
+        &labels,
+        10,
+        0.1,
+        observed(&order, &mut observer),
+    )
+    .unwrap();
+
+    assert_eq!(
+        observer.steps,
+        vec![
+            (1, 1, 3),
+            (1, 2, 1),
+            (1, 3, 0),
+            (1, 4, 2),
+            (2, 5, 3),
+            (2, 6, 1)
+        ]
+    );
+    assert_eq!(observer.epochs, 1);
+}
+
+#[test]
+fn early_stop_averages_over_steps_taken() {
+    let (feature_matrix, labels) = separable_data();
+    let order = [0, 1, 2, 3];
+    let mut observer = StopAfter {
+        limit: 8,
+        steps: Vec::new(),
+        epochs: 0,
+    };
+    let stopped = average_perceptron_with_options(
+        &feature_matrix,
+        &labels,
+        10,
+        observed(&order, &mut observer),
+    )
+    .unwrap();
+    let full =
+        average_perceptron_with_options(&feature_matrix, &labels, 2, in_order(&order)).unwrap();
+    assert_eq!(stopped, full);
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::{Algorithm, LinearModel};
+use automatic_review_analyzer::online::OnlineLearner;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::{
+    average_pegasos_with_options, average_perceptron_with_options, pegasos_with_options,
+    perceptron_with_options, DType,
+};
+
+use common::{feature_matrix, LABELS, LAMBDA, ROWS};
+
+const ORDER: [usize; 6] = [0, 1, 2, 3, 4, 5];
+
+fn in_order() -> TrainOptions<'static> {
+    TrainOptions {
+        order: Some(&ORDER),
+        ..Default::default()
+    }
+}
+
+/// Feeds the data to the learner `t` times in two batches per pass.
+fn fit_in_batches(learner: &mut OnlineLearner, t: usize) {
+    let first = FeatureMatrix::from_rows(&ROWS[..4]).unwrap();
+    let second = FeatureMatrix::from_rows(&ROWS[4..]).unwrap();
+    for _ in 0..t {
+        learner.partial_fit(&first, &LABELS[..4]).unwrap();
+        learner.partial_fit(&second, &LABELS[4..]).unwrap();
+    }
+}
+
+#[test]
+fn batches_match_a_single_run() {
+    let feature_matrix = feature_matrix();
+    let expected = [
+        perceptron_with_options(&feature_matrix, &LABELS, 3, in_order()).unwrap(),
+        average_perceptron_with_options(&feature_matrix, &LABELS, 3, in_order()).unwrap(),
+        pegasos_with_options(&feature_matrix, &LABELS, 3, LAMBDA, in_order()).unwrap(),
+        average_pegasos_with_options(&feature_matrix, &LABELS, 3, LAMBDA, 1., in_order()).unwrap(),
+    ];
+
+    for (algorithm, (theta, theta_0)) in Algorithm::ALL.into_iter().zip(expected) {
+        let mut learner = OnlineLearner::new(algorithm, 3, LAMBDA).unwrap();
+        fit_in_batches(&mut learner, 3);
+        assert_eq!(learner.steps(), 18);
+        let model = learner.model();
+        for (found, expected) in model.theta.iter().zip(&theta) {
+            assert!((found - expected).abs() < 1e-5, "{algorithm}: {model:?}");
+        }
+        assert!((model.theta_0 - theta_0).abs() < 1e-5, "{algorithm}");
+    }
+}
+
+#[test]
+fn from_model_carries_over_the_pegasos_step_counter() {
+    let feature_matrix = feature_matrix();
+    let (theta, theta_0) =
+        pegasos_with_options(&feature_matrix, &LABELS, 1, LAMBDA, in_order()).unwrap();
+    let expected = pegasos_with_options(&feature_matrix, &LABELS, 2, LAMBDA, in_order()).unwrap();
+
+    let model = LinearModel::new(theta, theta_0);
+    let mut learner = OnlineLearner::from_model(Algorithm::Pegasos, model, LAMBDA, 6).unwrap();
+    learner.partial_fit(&feature_matrix, &LABELS).unwrap();
+    assert_eq!(learner.steps(), 12);
+    assert_eq!(learner.model(), LinearModel::from(expected));
+}
+
+#[test]
+fn from_model_averages_only_new_iterates() {
+    let feature_matrix = feature_matrix();
+    let start = LinearModel::new(vec![1., -1., 0.5], 0.5);
+
+    let mut learner =
+        OnlineLearner::from_model(Algorithm::AveragePerceptron, start.clone(), LAMBDA, 0).unwrap();
+    assert_eq!(learner.model(), start);
+    learner.partial_fit(&feature_matrix, &LABELS).unwrap();
+
+    let mut plain = OnlineLearner::from_model(Algorithm::Perceptron, start, LAMBDA, 0).unwrap();
+    let mut iterates = Vec::new();
+    for (row, &label) in ROWS.iter().zip(&LABELS) {
+        let single = FeatureMatrix::from_rows(&[*row]).unwrap();
+        plain.partial_fit(&single, &[label]).unwrap();
+        iterates.push(plain.model());
+    }
+    let model = learner.model();
+    for j in 0..3 {
+        let mean = iterates.iter().map(|m| m.theta[j]).sum::<DType>() / 6.;
+        assert!((model.theta[j] - mean).abs() < 1e-5);
+    }
+    let mean_0 = iterates.iter().map(|m| m.theta_0).sum::<DType>() / 6.;
+    assert!((model.theta_0 - mean_0).abs() < 1e-5);
+}
+
+#[test]
+fn saved_learner_continues_like_an_uninterrupted_one() {
+    for algorithm in Algorithm::ALL {
+        let mut uninterrupted = OnlineLearner::new(algorithm, 3, LAMBDA).unwrap();
+        fit_in_batches(&mut uninterrupted, 2);
+
+        let mut learner = OnlineLearner::new(algorithm, 3, LAMBDA).unwrap();
+        fit_in_batches(&mut learner, 1);
+        let path =
+            std::env::temp_dir().join(format!("automatic_review_analyzer_{algorithm}.learner"));
+        let path = path.to_str().unwrap();
+        learner.save(path).unwrap();
+        let mut loaded = OnlineLearner::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded.to_text(), learner.to_text());
+        fit_in_batches(&mut loaded, 1);
+
+        assert_eq!(loaded.steps(), uninterrupted.steps());
+        assert_eq!(loaded.model(), uninterrupted.model(), "{algorithm}");
+        assert_eq!(loaded.to_text(), uninterrupted.to_text());
+    }
+}
+
+#[test]
+fn parse_rejects_invalid_learners() {
+    let mut learner = OnlineLearner::new(Algorithm::AveragePerceptron, 3, LAMBDA).unwrap();
+    fit_in_batches(&mut learner, 1);
+    let text = learner.to_text();
+    assert!(
+        text.starts_with("automatic_review_analyzer learner 1\nalgorithm\taverage_perceptron\n")
+    );
+
+    let cases = [
+        text.replace("learner 1", "learner 2"),
+        text.replace("steps\t6", "steps\tsix"),
+        text.lines().take(10).collect::<Vec<_>>().join("\n"),
+        text.replacen("\t", "@", 9),
+        format!("{text}extra\n"),
+        text.replace("n_feature\t3", &format!("n_feature\t{}", usize::MAX)),
+    ];
+    for case in cases {
+        assert!(
+            matches!(
+                OnlineLearner::<DType>::parse(&case),
+                Err(Error::Parse { .. })
+            ),
+            "{case}"
+        );
+    }
+}
+
+#[test]
+fn invalid_batches_leave_the_learner_unchanged() {
+    let mut learner = OnlineLearner::new(Algorithm::Pegasos, 3, LAMBDA).unwrap();
+    let wrong_width = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
+    assert!(matches!(
+        learner.partial_fit(&wrong_width, &[1.]),
+        Err(Error::DimensionMismatch { .. })
+    ));
+    let batch = FeatureMatrix::from_rows(&ROWS[..2]).unwrap();
+    assert!(matches!(
+        learner.partial_fit(&batch, &[1., 0.]),
+        Err(Error::InvalidLabel { index: 1, .. })
+    ));
+    assert_eq!(learner.steps(), 0);
+    assert_eq!(learner.model(), LinearModel::zeros(3));
+}
+
+#[test]
+fn lambda_is_checked_for_pegasos_only() {
+    assert!(matches!(
+        OnlineLearner::new(Algorithm::AveragePegasos, 3, -1.),
+        Err(Error::InvalidParameter { name: "lambda", .. })
+    ));
+    assert!(OnlineLearner::new(Algorithm::Perceptron, 3, -1.).is_ok());
+}
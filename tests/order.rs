@@ -0,0 +1,50 @@
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::order::{
+    get_order, load_order, natural_order, parse_order, shuffled_order,
+};
+use automatic_review_analyzer::{average_perceptron_with_options, perceptron};
+
+#[test]
+fn get_order_matches_reference_shuffle() {
+    assert_eq!(get_order(2), [1, 0]);
+    assert_eq!(get_order(5), [2, 3, 4, 0, 1]);
+    assert_eq!(get_order(10), [6, 8, 9, 7, 5, 3, 0, 4, 1, 2]);
+    assert_eq!(get_order(0), Vec::<usize>::new());
+}
+#[test]
+fn shuffled_order_large_seed() {
+    assert_eq!(
+        shuffled_order(20, (1 << 40) + 5),
+        [9, 7, 17, 13, 4, 18, 5, 6, 10, 11, 3, 14, 12, 15, 2, 1, 0, 8, 19, 16]
+    );
+}
+#[test]
+fn get_order_uses_data_files() {
+    assert_eq!(get_order(200), load_order("data/200.txt").unwrap());
+    assert_eq!(get_order(4000), load_order("data/4000.txt").unwrap());
+    assert_eq!(&get_order(200)[..3], [131, 181, 22]);
+}
+#[test]
+fn parse_order_rejects_non_permutations() {
+    assert_eq!(parse_order("2,0,1\n").unwrap(), [2, 0, 1]);
+    assert!(parse_order("0,0,1").is_err());
+    assert!(parse_order("0,3,1").is_err());
+    assert!(parse_order("0,a,1").is_err());
+}
+#[test]
+fn training_with_natural_order() {
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.], [-1., 0.]]).unwrap();
+    let labels = [1., 1.];
+
+    let order = natural_order(2);
+    let options = TrainOptions {
+        order: Some(&order),
+        ..Default::default()
+    };
+    let result = average_perceptron_with_options(&feature_matrix, &labels, 1, options).unwrap();
+    assert_eq!(result, (vec![0.5, 2.], 1.5));
+
+    let result = perceptron(&feature_matrix, &labels, 1).unwrap();
+    assert_eq!(result, (vec![0., 2.], 2.));
+}
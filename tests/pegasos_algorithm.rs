@@ -1,4 +1,10 @@
-use automatic_review_analyzer::{pegasos, pegasos_single_step_update, DType};
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::{
+    average_pegasos, average_pegasos_with_options, pegasos, pegasos_single_step_update,
+    pegasos_single_step_update_in_place, DType,
+};
 
 const EPSILON: DType = 1e-6;
 
@@ -11,7 +17,8 @@ fn pegasos_single_test_1() {
     let lambda = 0.2;
     let eta = 0.1;
     let exp_result = ([-0.88, 1.18], -1.4);
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -36,7 +43,8 @@ fn pegasos_single_test_2() {
     let lambda = 0.2;
     let eta = 0.1;
     let exp_result = ([-0.88, 1.08], 1.1);
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -61,7 +69,8 @@ fn pegasos_single_test_3() {
     let lambda = 0.2;
     let eta = 0.1;
     let exp_result = ([-0.88, 1.18], -1.9);
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -114,7 +123,8 @@ fn pegasos_single_label_gt_1() {
         ],
         -2.0204571,
     );
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -167,7 +177,8 @@ fn pegasos_single_label_eq_1() {
         ],
         -1.3961514,
     );
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -220,7 +231,8 @@ fn pegasos_single_label_lt_1() {
         ],
         0.8742860,
     );
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -273,7 +285,8 @@ fn pegasos_single_label_le_0() {
         ],
         0.0848014,
     );
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -315,7 +328,8 @@ fn pegasos_single_theta_eq_0() {
         ],
         0.8978079,
     );
-    let result = pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
+    let result: (Vec<DType>, DType) =
+        pegasos_single_step_update(&feature_vector, label, lambda, eta, &theta, theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -333,13 +347,13 @@ fn pegasos_single_theta_eq_0() {
 }
 #[test]
 fn pegasos_test_1() {
-    let feature_matrix = vec![vec![1., 2.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
     let labels = [1.];
     let t = 1;
     let lambda = 0.2;
 
     let exp_result = ([1., 2.], 1.);
-    let result = pegasos(&feature_matrix, &labels, t, lambda);
+    let result: (Vec<DType>, DType) = pegasos(&feature_matrix, &labels, t, lambda).unwrap();
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -357,7 +371,7 @@ fn pegasos_test_1() {
 }
 #[test]
 fn pegasos_test_2() {
-    let feature_matrix = vec![vec![1., 1.], vec![1., 1.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 1.], [1., 1.]]).unwrap();
     let labels = [1., 1.];
     let t = 1;
     let lambda = 1.;
@@ -366,7 +380,7 @@ fn pegasos_test_2() {
         [1. - 1. / DType::sqrt(2f32), 1. - 1. / DType::sqrt(2f32)],
         1.,
     );
-    let result = pegasos(&feature_matrix, &labels, t, lambda);
+    let result: (Vec<DType>, DType) = pegasos(&feature_matrix, &labels, t, lambda).unwrap();
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
         "{} is not approximately equal to {}",
@@ -384,8 +398,8 @@ fn pegasos_test_2() {
 }
 #[test]
 fn pegasos_high_dimension() {
-    let feature_matrix = vec![
-        vec![
+    let feature_matrix = FeatureMatrix::from_rows(&[
+        [
             0.1837462,
             0.29989789,
             -0.35889786,
@@ -397,7 +411,7 @@ fn pegasos_high_dimension() {
             -0.40850817,
             -0.13105809,
         ],
-        vec![
+        [
             0.08254096,
             0.06012654,
             0.19821234,
@@ -409,7 +423,7 @@ fn pegasos_high_dimension() {
             -0.27312663,
             0.39060785,
         ],
-        vec![
+        [
             -0.20112519,
             -0.00593087,
             0.05738862,
@@ -421,7 +435,7 @@ fn pegasos_high_dimension() {
             0.2901038,
             -0.29736505,
         ],
-        vec![
+        [
             -0.14703536,
             -0.45573697,
             -0.47563745,
@@ -433,7 +447,7 @@ fn pegasos_high_dimension() {
             -0.02759763,
             0.0297091,
         ],
-        vec![
+        [
             -0.18082261,
             0.28644149,
             -0.47549449,
@@ -445,7 +459,8 @@ fn pegasos_high_dimension() {
             0.21849356,
             -0.01642202,
         ],
-    ];
+    ])
+    .unwrap();
     let labels = [-1., -1., -1., 1., -1.];
     let t = 10;
     let lambda = 0.1456692551041303;
@@ -457,7 +472,7 @@ fn pegasos_high_dimension() {
         ],
         1.,
     );
-    let result = pegasos(&feature_matrix, &labels, t, lambda);
+    let result: (Vec<DType>, DType) = pegasos(&feature_matrix, &labels, t, lambda).unwrap();
     // assert!(
     //     (result.1 - exp_result.1).abs() < EPSILON,
     //     "{} is not approximately equal to {}",
@@ -473,3 +488,92 @@ fn pegasos_high_dimension() {
         }
     }
 }
+#[test]
+fn pegasos_single_step_update_in_place_matches_pure() {
+    let feature_vector = [1., -2., 0.5];
+    let theta = [0.25, 0.5, -1.];
+
+    for (label, theta_0) in [(1., -0.5), (-1., -0.5), (1., 3.)] {
+        let expected =
+            pegasos_single_step_update(&feature_vector, label, 0.1, 0.5, &theta, theta_0);
+        let mut new_theta = theta.to_vec();
+        let mut new_theta_0 = theta_0;
+        let updated = pegasos_single_step_update_in_place(
+            &feature_vector,
+            label,
+            0.1,
+            0.5,
+            &mut new_theta,
+            &mut new_theta_0,
+        );
+
+        assert_eq!((new_theta, new_theta_0), expected);
+        assert_eq!(updated, expected.1 != theta_0);
+    }
+}
+#[test]
+fn average_pegasos_averages_iterates() {
+    let rows = [[1., 0.5, -2.], [-1.5, 2., 0.25], [0.75, -1., 1.]];
+    let labels = [1., -1., 1.];
+    let feature_matrix: FeatureMatrix = FeatureMatrix::from_rows(&rows).unwrap();
+    let order = [2, 0, 1];
+    let (t, lambda) = (4, 0.1);
+
+    let mut iterates = Vec::new();
+    let (mut theta, mut theta_0): (Vec<DType>, DType) = (vec![0.; 3], 0.);
+    let mut count = 0;
+    for _ in 0..t {
+        for &i in &order {
+            count += 1;
+            let eta = 1. / (count as DType).sqrt();
+            (theta, theta_0) =
+                pegasos_single_step_update(&rows[i], labels[i], lambda, eta, &theta, theta_0);
+            iterates.push((theta.clone(), theta_0));
+        }
+    }
+    let average = |iterates: &[(Vec<DType>, DType)]| {
+        let n = iterates.len() as DType;
+        let theta: Vec<DType> = (0..3)
+            .map(|j| iterates.iter().map(|(theta, _)| theta[j]).sum::<DType>() / n)
+            .collect();
+        (
+            theta,
+            iterates.iter().map(|(_, theta_0)| theta_0).sum::<DType>() / n,
+        )
+    };
+
+    for (tail, n_averaged) in [(1., 12), (0.5, 6), (0.3, 4)] {
+        let options = TrainOptions {
+            order: Some(&order),
+            ..Default::default()
+        };
+        let result =
+            average_pegasos_with_options(&feature_matrix, &labels, t, lambda, tail, options)
+                .unwrap();
+        let expected = average(&iterates[12 - n_averaged..]);
+        for (&l, &r) in result.0.iter().zip(&expected.0) {
+            assert!((l - r).abs() < EPSILON, "tail {tail}: {l} != {r}");
+        }
+        assert!((result.1 - expected.1).abs() < EPSILON);
+    }
+
+    // A tail shorter than one step keeps only the last iterate.
+    let result = average_pegasos(&feature_matrix, &labels, t, lambda, 1e-9).unwrap();
+    let last = pegasos(&feature_matrix, &labels, t, lambda).unwrap();
+    assert_eq!(result, last);
+}
+#[test]
+fn average_pegasos_invalid_parameters() {
+    let feature_matrix: FeatureMatrix = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
+
+    for tail in [0., -0.5, 1.5, DType::NAN] {
+        assert!(matches!(
+            average_pegasos(&feature_matrix, &[1.], 1, 0.1, tail),
+            Err(Error::InvalidParameter { name: "tail", .. })
+        ));
+    }
+    assert!(matches!(
+        average_pegasos(&feature_matrix, &[1.], 0, 0.1, 1.),
+        Err(Error::InvalidParameter { name: "t", .. })
+    ));
+}
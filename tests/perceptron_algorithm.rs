@@ -1,4 +1,9 @@
-use automatic_review_analyzer::{average_perceptron, perceptron, perceptron_single_step_update, DType};
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::{
+    average_perceptron, average_perceptron_with_options, perceptron, perceptron_single_step_update,
+    perceptron_single_step_update_in_place, DType,
+};
 
 const EPSILON: DType = 1e-6;
 
@@ -37,7 +42,7 @@ fn perceptron_single_random_feature_theta() {
         ],
         0.7277219,
     );
-    let result =
+    let result: (Vec<DType>, DType) =
         perceptron_single_step_update(&feature_vector, label, &current_theta, current_theta_0);
 
     assert_eq!(result.1, exp_result.1);
@@ -86,7 +91,7 @@ fn perceptron_single_correct_prediction() {
         ],
         -0.9692424,
     );
-    let result =
+    let result: (Vec<DType>, DType) =
         perceptron_single_step_update(&feature_vector, label, &current_theta, current_theta_0);
     assert_eq!(result.1, exp_result.1);
     for (i, (&l, &r)) in result.0.iter().zip(exp_result.0.iter()).enumerate() {
@@ -134,7 +139,7 @@ fn perceptron_single_incorrect_prediction() {
         ],
         0.9710286,
     );
-    let result =
+    let result: (Vec<DType>, DType) =
         perceptron_single_step_update(&feature_vector, label, &current_theta, current_theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -187,7 +192,7 @@ fn perceptron_single_boundary_case_positive() {
         ],
         1.3362098,
     );
-    let result =
+    let result: (Vec<DType>, DType) =
         perceptron_single_step_update(&feature_vector, label, &current_theta, current_theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -240,7 +245,7 @@ fn perceptron_single_boundary_case_negative() {
         ],
         -0.8362101,
     );
-    let result =
+    let result: (Vec<DType>, DType) =
         perceptron_single_step_update(&feature_vector, label, &current_theta, current_theta_0);
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -259,12 +264,12 @@ fn perceptron_single_boundary_case_negative() {
 }
 #[test]
 fn perceptron_test_1() {
-    let feature_matrix = vec![vec![1., 2.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
     let labels = [1.];
     let t = 1;
 
     let exp_result = (vec![1., 2.], 1.);
-    let result = perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -283,12 +288,12 @@ fn perceptron_test_1() {
 }
 #[test]
 fn perceptron_test_2() {
-    let feature_matrix = vec![vec![1., 2.], vec![-1., 0.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.], [-1., 0.]]).unwrap();
     let labels = [1., 1.];
     let t = 1;
 
     let exp_result = (vec![0., 2.], 2.);
-    let result = perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -307,12 +312,12 @@ fn perceptron_test_2() {
 }
 #[test]
 fn perceptron_test_3() {
-    let feature_matrix = vec![vec![1., 2.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
     let labels = [1.];
     let t = 2;
 
     let exp_result = (vec![1., 2.], 1.);
-    let result = perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -331,12 +336,12 @@ fn perceptron_test_3() {
 }
 #[test]
 fn perceptron_test_4() {
-    let feature_matrix = vec![vec![1., 2.], vec![-1., 0.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.], [-1., 0.]]).unwrap();
     let labels = [1., 1.];
     let t = 2;
 
     let exp_result = (vec![0., 2.], 2.);
-    let result = perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -355,12 +360,12 @@ fn perceptron_test_4() {
 }
 #[test]
 fn average_perceptron_test_1() {
-    let feature_matrix = vec![vec![1., 2.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
     let labels = [1.];
     let t = 1;
 
     let exp_result = (vec![1., 2.], 1.);
-    let result = average_perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = average_perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -379,12 +384,12 @@ fn average_perceptron_test_1() {
 }
 #[test]
 fn average_perceptron_test_2() {
-    let feature_matrix = vec![vec![1., 2.], vec![-1., 0.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.], [-1., 0.]]).unwrap();
     let labels = [1., 1.];
     let t = 1;
 
     let exp_result = (vec![-0.5, 1.], 1.5);
-    let result = average_perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = average_perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -403,12 +408,12 @@ fn average_perceptron_test_2() {
 }
 #[test]
 fn average_perceptron_test_3() {
-    let feature_matrix = vec![vec![1., 2.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.]]).unwrap();
     let labels = [1.];
     let t = 2;
 
     let exp_result = (vec![1., 2.], 1.);
-    let result = average_perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = average_perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -427,12 +432,12 @@ fn average_perceptron_test_3() {
 }
 #[test]
 fn average_perceptron_test_4() {
-    let feature_matrix = vec![vec![1., 2.], vec![-1., 0.]];
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2.], [-1., 0.]]).unwrap();
     let labels = [1., 1.];
     let t = 2;
 
     let exp_result = (vec![-0.25, 1.5], 1.75);
-    let result = average_perceptron(&feature_matrix, &labels, t);
+    let result: (Vec<DType>, DType) = average_perceptron(&feature_matrix, &labels, t).unwrap();
 
     assert!(
         (result.1 - exp_result.1).abs() < EPSILON,
@@ -448,4 +453,66 @@ fn average_perceptron_test_4() {
             )
         }
     }
-}
\ No newline at end of file
+}
+#[test]
+fn perceptron_single_step_update_in_place_matches_pure() {
+    let feature_vector = [1., -2., 0.5];
+    let theta = [0.25, 0.5, -1.];
+
+    for (label, theta_0) in [(1., -0.5), (-1., -0.5), (1., 3.)] {
+        let expected = perceptron_single_step_update(&feature_vector, label, &theta, theta_0);
+        let mut new_theta = theta.to_vec();
+        let mut new_theta_0 = theta_0;
+        let updated = perceptron_single_step_update_in_place(
+            &feature_vector,
+            label,
+            &mut new_theta,
+            &mut new_theta_0,
+        );
+
+        assert_eq!((new_theta, new_theta_0), expected);
+        assert_eq!(updated, expected.1 != theta_0);
+    }
+}
+#[test]
+fn average_perceptron_matches_explicit_average() {
+    let rows = [
+        [0.5, 0., -1.2, 2.],
+        [0., 1.5, 0.3, 0.],
+        [-0.7, 0.4, 0., 0.9],
+        [1.25, -0.2, 0., 0.8],
+        [0., 0., -0.6, -0.1],
+    ];
+    let labels = [1., -1., 1., -1., 1.];
+    let feature_matrix: FeatureMatrix = FeatureMatrix::from_rows(&rows).unwrap();
+    let order = [3, 0, 4, 1, 2];
+
+    for t in [1, 2, 7, 50] {
+        let mut theta = vec![0.; 4];
+        let mut theta_0 = 0.;
+        let mut theta_sum = vec![0f64; 4];
+        let mut theta_0_sum = 0f64;
+        for _ in 0..t {
+            for &i in &order {
+                (theta, theta_0) =
+                    perceptron_single_step_update(&rows[i], labels[i], &theta, theta_0);
+                for (s, &x) in theta_sum.iter_mut().zip(&theta) {
+                    *s += x as f64;
+                }
+                theta_0_sum += theta_0 as f64;
+            }
+        }
+        let n = (t * order.len()) as f64;
+
+        let options = TrainOptions {
+            order: Some(&order),
+            ..Default::default()
+        };
+        let result: (Vec<DType>, DType) =
+            average_perceptron_with_options(&feature_matrix, &labels, t, options).unwrap();
+        for (&l, &r) in result.0.iter().zip(&theta_sum) {
+            assert!((l as f64 - r / n).abs() < 1e-6, "{l} != {}", r / n);
+        }
+        assert!((result.1 as f64 - theta_0_sum / n).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,157 @@
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::features::{bag_of_words, extract_bow_feature_vectors, Dictionary};
+use automatic_review_analyzer::model::{Algorithm, LinearModel};
+use automatic_review_analyzer::persist::SavedModel;
+use automatic_review_analyzer::{pegasos, DType};
+
+fn saved_model() -> SavedModel {
+    SavedModel::new(
+        Algorithm::Pegasos,
+        10,
+        Some(0.01),
+        None,
+        LinearModel::new(vec![0.5, -0.75, 1. / 3.], -0.25),
+        Some(Dictionary::from_words(["great", "awful", "fine"])),
+    )
+    .unwrap()
+}
+
+#[test]
+fn to_text_layout() {
+    assert_eq!(
+        saved_model().to_text(),
+        "automatic_review_analyzer model 1\n\
+         algorithm\tpegasos\n\
+         t\t10\n\
+         lambda\t0.01\n\
+         tail\t\n\
+         theta_0\t-0.25\n\
+         n_feature\t3\n\
+         0.5\tgreat\n\
+         -0.75\tawful\n\
+         0.33333334\tfine\n"
+    );
+}
+#[test]
+fn round_trip_is_exact() {
+    let saved = saved_model();
+    assert_eq!(SavedModel::parse(&saved.to_text()).unwrap(), saved);
+
+    let saved = SavedModel::new(
+        Algorithm::AveragePerceptron,
+        5,
+        None,
+        None,
+        LinearModel::new(vec![1e-30, 3.4e38, -0.1], 7.),
+        None,
+    )
+    .unwrap();
+    assert_eq!(SavedModel::parse(&saved.to_text()).unwrap(), saved);
+
+    let saved = SavedModel::new(
+        Algorithm::AveragePegasos,
+        5,
+        Some(0.1),
+        Some(0.4),
+        LinearModel::new(vec![0.25, -0.1], 0.5),
+        None,
+    )
+    .unwrap();
+    assert!(saved.to_text().contains("\ntail\t0.4\n"));
+    assert_eq!(SavedModel::parse(&saved.to_text()).unwrap(), saved);
+}
+#[test]
+fn save_and_load_trained_model() {
+    let texts = ["good food", "bad food", "good", "bad bad"];
+    let labels = [1., -1., 1., -1.];
+    let dictionary = bag_of_words(&texts, None);
+    let feature_matrix = extract_bow_feature_vectors(&texts, &dictionary);
+    let model = LinearModel::from(pegasos(&feature_matrix, &labels, 10, 0.1).unwrap());
+    let saved = SavedModel::new(
+        Algorithm::Pegasos,
+        10,
+        Some(0.1),
+        None,
+        model,
+        Some(dictionary),
+    )
+    .unwrap();
+
+    let path = std::env::temp_dir().join("automatic_review_analyzer_persist_test.model");
+    let path = path.to_str().unwrap();
+    saved.save(path).unwrap();
+    let loaded = SavedModel::load(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(loaded, saved);
+    let loaded_features = extract_bow_feature_vectors(&texts, loaded.vocabulary.as_ref().unwrap());
+    assert_eq!(
+        loaded.model.predict_batch(&loaded_features).unwrap(),
+        saved.model.predict_batch(&feature_matrix).unwrap()
+    );
+}
+#[test]
+fn new_validates_settings() {
+    let model = LinearModel::new(vec![1., 2.], 0.);
+
+    assert!(matches!(
+        SavedModel::new(
+            Algorithm::Perceptron,
+            1,
+            None,
+            None,
+            model.clone(),
+            Some(Dictionary::from_words(["a"]))
+        ),
+        Err(Error::DimensionMismatch {
+            expected: 2,
+            found: 1,
+            ..
+        })
+    ));
+    let new =
+        |algorithm, lambda, tail| SavedModel::new(algorithm, 1, lambda, tail, model.clone(), None);
+    assert!(new(Algorithm::Pegasos, None, None).is_err());
+    assert!(new(Algorithm::AveragePegasos, None, Some(1.)).is_err());
+    assert!(new(Algorithm::AveragePegasos, Some(0.1), Some(1.)).is_ok());
+    assert!(matches!(
+        new(Algorithm::AveragePegasos, Some(0.1), None),
+        Err(Error::InvalidParameter { name: "tail", .. })
+    ));
+    assert!(matches!(
+        new(Algorithm::Pegasos, Some(0.1), Some(1.)),
+        Err(Error::InvalidParameter { name: "tail", .. })
+    ));
+    assert!(new(Algorithm::Perceptron, Some(0.1), None).is_err());
+}
+#[test]
+fn parse_rejects_invalid_files() {
+    let text = saved_model().to_text();
+    let parse_line = |text: &str| match SavedModel::<DType>::parse(text) {
+        Err(Error::Parse { line, .. }) => line,
+        result => panic!("expected a parse error, got {result:?}"),
+    };
+
+    assert_eq!(parse_line(""), 1);
+    assert_eq!(parse_line(&text.replace("model 1", "model 2")), 1);
+    assert_eq!(parse_line(&text.replace("pegasos", "svm")), 2);
+    assert_eq!(parse_line(&text.replace("t\t10", "t\tten")), 3);
+    assert_eq!(parse_line(&text.replace("tail\t", "tail\tall")), 5);
+    assert_eq!(parse_line(&text.replace("tail\t\n", "")), 5);
+    assert_eq!(parse_line(&text.replace("theta_0\t", "offset\t")), 6);
+    assert_eq!(
+        parse_line(&text.replace("n_feature\t3", "n_feature\t4")),
+        11
+    );
+    assert_eq!(
+        parse_line(&text.replace("n_feature\t3", "n_feature\t2")),
+        10
+    );
+    assert_eq!(parse_line(&text.replace("\tawful", "")), 9);
+    assert_eq!(parse_line(&text.replace("awful", "great")), 9);
+    assert_eq!(parse_line(&text.replace("-0.75", "NaN")), 9);
+    assert_eq!(
+        parse_line(&text.replace("n_feature\t3", &format!("n_feature\t{}", usize::MAX))),
+        11
+    );
+}
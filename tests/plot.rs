@@ -0,0 +1,63 @@
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::plot::plot_toy_data;
+
+fn toy_data() -> (FeatureMatrix, Vec<f32>) {
+    let feature_matrix =
+        FeatureMatrix::from_rows(&[[0., 0.], [10., 10.], [0., 10.], [10., 0.]]).unwrap();
+    (feature_matrix, vec![-1., 1., 1., -1.])
+}
+
+#[test]
+fn plot_points_and_boundary() {
+    let (feature_matrix, labels) = toy_data();
+    let svg = plot_toy_data("Perceptron", &feature_matrix, &labels, &(vec![0., 1.], -5.)).unwrap();
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains(">Perceptron</text>"));
+    // Four data points plus the two legend markers.
+    assert_eq!(svg.matches("<circle").count(), 6);
+    assert_eq!(svg.matches("fill=\"#1f77b4\"").count(), 3);
+    // The horizontal line y = 5 crosses the middle of the plot from side to side.
+    assert!(svg.contains(r#"<line x1="50.00" y1="300.00" x2="550.00" y2="300.00""#));
+}
+#[test]
+fn plot_without_boundary() {
+    let (feature_matrix, labels) = toy_data();
+
+    let svg = plot_toy_data("zero", &feature_matrix, &labels, &(vec![0., 0.], 0.)).unwrap();
+    assert!(!svg.contains("<line"));
+    let svg = plot_toy_data("far", &feature_matrix, &labels, &(vec![1., 0.], 100.)).unwrap();
+    assert!(!svg.contains("<line"));
+}
+#[test]
+fn plot_escapes_title() {
+    let (feature_matrix, labels) = toy_data();
+    let svg = plot_toy_data("a<b & c", &feature_matrix, &labels, &(vec![1., 1.], 0.)).unwrap();
+
+    assert!(svg.contains(">a&lt;b &amp; c</text>"));
+}
+#[test]
+fn plot_rejects_invalid_input() {
+    let (feature_matrix, labels) = toy_data();
+    let thetas = (vec![1., 1.], 0.);
+
+    assert!(matches!(
+        plot_toy_data("", &feature_matrix, &labels[..3], &thetas),
+        Err(Error::DimensionMismatch { .. })
+    ));
+    assert!(matches!(
+        plot_toy_data("", &feature_matrix, &labels, &(vec![1.], 0.)),
+        Err(Error::DimensionMismatch { .. })
+    ));
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 2., 3.]]).unwrap();
+    assert!(matches!(
+        plot_toy_data("", &feature_matrix, &[1.], &thetas),
+        Err(Error::DimensionMismatch {
+            expected: 2,
+            found: 3,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,118 @@
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::reviews::{
+    fill_sentiment, labels, load_reviews, parse_reviews, write_submission,
+};
+
+const HEADER: &str = "sentiment\tproductId\tuserId\tsummary\ttext\thelpfulY\thelpfulN\n";
+
+#[test]
+fn parse_reviews_quoted_fields() {
+    let contents = format!(
+        "{HEADER}-1\tB0\tU0\t\"The Box says \"\"OATMEAL\"\"\"\t\"a\tb\"\t1\t2\r\n1\tB1\tU1\tok\tfine\t0\t0\r\n"
+    );
+    let reviews = parse_reviews(&contents).unwrap();
+
+    assert_eq!(reviews.len(), 2);
+    assert_eq!(reviews[0].sentiment, Some(-1));
+    assert_eq!(reviews[0].summary, "The Box says \"OATMEAL\"");
+    assert_eq!(reviews[0].text, "a\tb");
+    assert_eq!((reviews[0].helpful_y, reviews[0].helpful_n), (1, 2));
+    assert_eq!(reviews[1].sentiment, Some(1));
+    assert_eq!(reviews[1].text, "fine");
+}
+#[test]
+fn parse_reviews_empty_sentiment() {
+    let contents = format!("{HEADER}\t\tU0\tok\tfine\t0\t0\n");
+    let reviews = parse_reviews(&contents).unwrap();
+
+    assert_eq!(reviews[0].sentiment, None);
+    assert_eq!(reviews[0].product_id, "");
+    assert!(labels(&reviews).is_err());
+}
+#[test]
+fn parse_reviews_reports_line() {
+    let contents = format!("{HEADER}1\tB0\tU0\tok\tfine\t0\t0\n2\tB1\tU1\tok\tfine\t0\t0\n");
+    let err = parse_reviews(&contents).unwrap_err();
+
+    assert!(err.to_string().starts_with("line 3:"), "{err}");
+}
+#[test]
+fn parse_reviews_wrong_header() {
+    assert!(parse_reviews("label\ttext\n1\tfine\n").is_err());
+}
+#[test]
+fn load_reviews_datasets() {
+    for (path, n) in [
+        ("data/reviews_train.tsv", 4000),
+        ("data/reviews_val.tsv", 500),
+        ("data/reviews_test.tsv", 500),
+    ] {
+        let reviews = load_reviews(path).unwrap();
+        assert_eq!(reviews.len(), n);
+        assert_eq!(labels(&reviews).unwrap().len(), n);
+    }
+    let submit = load_reviews("data/reviews_submit.tsv").unwrap();
+    assert_eq!(submit.len(), 500);
+    assert!(submit.iter().all(|review| review.sentiment.is_none()));
+}
+#[test]
+fn fill_sentiment_preserves_other_bytes() {
+    let contents = format!(
+        "{HEADER}\tB0\tU0\t\"say \"\"hi\"\"\"\t\"a\tb\"\t1\t2\r\n\"\"\tB1\tU1\tok\tfine\t0\t0\r\n-1\tB2\tU2\tok\tbad\t0\t0"
+    );
+
+    assert_eq!(
+        fill_sentiment(&contents, &[1., -1., 1.]).unwrap(),
+        format!(
+            "{HEADER}1\tB0\tU0\t\"say \"\"hi\"\"\"\t\"a\tb\"\t1\t2\r\n-1\tB1\tU1\tok\tfine\t0\t0\r\n1\tB2\tU2\tok\tbad\t0\t0"
+        )
+    );
+    assert!(matches!(
+        fill_sentiment(&contents, &[1., -1.]),
+        Err(Error::DimensionMismatch {
+            expected: 3,
+            found: 2,
+            ..
+        })
+    ));
+    assert!(matches!(
+        fill_sentiment(&contents, &[1., 0., 1.]),
+        Err(Error::InvalidLabel { index: 1, .. })
+    ));
+}
+#[test]
+fn write_submission_file() {
+    let input = std::fs::read("data/reviews_submit.tsv").unwrap();
+    let reviews = load_reviews("data/reviews_submit.tsv").unwrap();
+    let preds: Vec<f32> = (0..reviews.len())
+        .map(|i| if i % 3 == 0 { -1. } else { 1. })
+        .collect();
+
+    let path = std::env::temp_dir().join("automatic_review_analyzer_submission_test.tsv");
+    let path = path.to_str().unwrap();
+    write_submission("data/reviews_submit.tsv", path, &preds).unwrap();
+    let output = std::fs::read(path).unwrap();
+    let filled = load_reviews(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    let labels = labels(&filled).unwrap();
+    assert_eq!(labels, preds);
+    // Only the sentiment fields, which were empty, are inserted.
+    let inserted = preds
+        .iter()
+        .map(|&p| if p > 0. { 1 } else { 2 })
+        .sum::<usize>();
+    assert_eq!(output.len(), input.len() + inserted);
+    for (review, original) in filled.iter().zip(&reviews) {
+        assert_eq!(review.text, original.text);
+        assert_eq!(review.summary, original.summary);
+    }
+    let strip = |bytes: &[u8]| -> Vec<u8> {
+        bytes
+            .iter()
+            .copied()
+            .filter(|&b| b != b'1' && b != b'-')
+            .collect()
+    };
+    assert_eq!(strip(&output), strip(&input));
+}
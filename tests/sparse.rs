@@ -0,0 +1,225 @@
+use automatic_review_analyzer::features::{
+    bag_of_words, extract_bow_feature_vectors, extract_bow_sparse_feature_vectors,
+};
+use automatic_review_analyzer::matrix::{FeatureMatrix, ShapeError};
+use automatic_review_analyzer::model::LinearModel;
+use automatic_review_analyzer::observer::EpochHistory;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::sparse::{
+    average_perceptron_sparse, average_perceptron_sparse_with_options, hinge_loss_full_sparse,
+    hinge_loss_single_sparse, pegasos_sparse, pegasos_sparse_with_options, perceptron_sparse,
+    perceptron_sparse_with_options, ScaledVector, SparseMatrix, SparseVector,
+};
+use automatic_review_analyzer::{
+    average_perceptron, average_perceptron_with_options, hinge_loss_full, pegasos,
+    pegasos_with_options, perceptron, perceptron_with_options, DType,
+};
+
+const EPSILON: DType = 1e-5;
+
+fn assert_all_approx_eq(left: &[DType], right: &[DType]) {
+    assert_eq!(left.len(), right.len());
+    for (i, (&l, &r)) in left.iter().zip(right.iter()).enumerate() {
+        assert!((l - r).abs() < EPSILON, "index {i}: {l} != {r}");
+    }
+}
+
+fn sample_matrix() -> FeatureMatrix {
+    FeatureMatrix::from_rows(&[
+        [0.5, 0., 0., -1.2, 0.],
+        [0., 0., 0.3, 0., 0.],
+        [-0.7, 0.4, 0., 0., 0.9],
+        [0., -0.2, 0., 0.8, 0.],
+        [0., 0., -0.6, 0., -0.1],
+        [1.1, 0., 0., 0., 0.],
+    ])
+    .unwrap()
+}
+
+#[test]
+fn sparse_vector_dot_and_dense() {
+    let indices = [1, 4];
+    let values = [2., -1.];
+    let x = SparseVector::new(&indices, &values).unwrap();
+
+    assert_eq!(x.nnz(), 2);
+    assert_eq!(x.dot(&[9., 1., 9., 9., 3.]), -1.);
+    assert_eq!(x.to_dense(5), [0., 2., 0., 0., -1.]);
+    assert_eq!(
+        SparseVector::new(&[2, 1], &[1., 1.]),
+        Err(ShapeError::UnsortedIndices { position: 1 })
+    );
+    assert!(SparseVector::new(&[1], &[1., 1.]).is_err());
+}
+#[test]
+fn sparse_matrix_round_trip() {
+    let dense = sample_matrix();
+    let sparse = SparseMatrix::from_dense(&dense);
+
+    assert_eq!(sparse.shape(), (6, 5));
+    assert_eq!(sparse.nnz(), 11);
+    assert_eq!(sparse.row(2).indices(), [0, 1, 4]);
+    assert_eq!(sparse.to_dense(), dense);
+
+    let mut sparse = SparseMatrix::new(3);
+    assert_eq!(
+        sparse.push_row(&[3], &[1.]),
+        Err(ShapeError::IndexOutOfBounds {
+            index: 3,
+            n_cols: 3
+        })
+    );
+    sparse.push_row(&[], &[]).unwrap();
+    assert_eq!(sparse.row(0).nnz(), 0);
+}
+#[test]
+fn scaled_vector_operations() {
+    let indices = [0, 2];
+    let values = [1., 2.];
+    let x = SparseVector::new(&indices, &values).unwrap();
+    let mut theta = ScaledVector::from_vec(vec![1., 1., 1.]);
+
+    theta.scale_by(0.5);
+    theta.add_scaled(2., x);
+    assert_eq!(theta.to_vec(), [2.5, 0.5, 4.5]);
+    assert_eq!(theta.get(2), 4.5);
+    assert_eq!(theta.dot(x), 11.5);
+
+    theta.scale_by(0.);
+    assert_eq!(theta.to_vec(), [0., 0., 0.]);
+}
+#[test]
+fn sparse_hinge_loss_matches_dense() {
+    let dense = sample_matrix();
+    let sparse = SparseMatrix::from_dense(&dense);
+    let labels = [1., -1., 1., -1., 1., -1.];
+    let theta = [0.3, -0.5, 1., 0.2, -0.4];
+
+    assert!(
+        (hinge_loss_full_sparse(&sparse, &labels, &theta, 0.1).unwrap()
+            - hinge_loss_full(&dense, &labels, &theta, 0.1).unwrap())
+        .abs()
+            < EPSILON
+    );
+    assert_eq!(
+        hinge_loss_single_sparse(sparse.row(1), -1., &theta, 0.),
+        1.3
+    );
+}
+#[test]
+fn sparse_training_matches_dense() {
+    let dense = sample_matrix();
+    let sparse = SparseMatrix::from_dense(&dense);
+    let labels = [1., -1., 1., -1., 1., -1.];
+
+    for t in [1, 5, 20] {
+        let (theta, theta_0) = perceptron(&dense, &labels, t).unwrap();
+        let (sparse_theta, sparse_theta_0) = perceptron_sparse(&sparse, &labels, t).unwrap();
+        assert_all_approx_eq(&sparse_theta, &theta);
+        assert_eq!(sparse_theta_0, theta_0);
+
+        let (theta, theta_0) = average_perceptron(&dense, &labels, t).unwrap();
+        let (sparse_theta, sparse_theta_0) =
+            average_perceptron_sparse(&sparse, &labels, t).unwrap();
+        assert_eq!(sparse_theta, theta);
+        assert_eq!(sparse_theta_0, theta_0);
+
+        for lambda in [0.01, 0.2, 1.] {
+            let (theta, theta_0) = pegasos(&dense, &labels, t, lambda).unwrap();
+            let (sparse_theta, sparse_theta_0) =
+                pegasos_sparse(&sparse, &labels, t, lambda).unwrap();
+            assert_all_approx_eq(&sparse_theta, &theta);
+            assert!((sparse_theta_0 - theta_0).abs() < EPSILON);
+        }
+    }
+}
+/// Options visiting the samples in `order`, starting from `initial` and
+/// recording to `history`.
+fn options<'a>(
+    order: &'a [usize],
+    initial: &'a LinearModel,
+    history: &'a mut EpochHistory,
+) -> TrainOptions<'a> {
+    TrainOptions {
+        order: Some(order),
+        observer: Some(history),
+        initial: Some(initial),
+    }
+}
+
+#[test]
+fn sparse_training_with_options_matches_dense() {
+    let dense = sample_matrix();
+    let sparse = SparseMatrix::from_dense(&dense);
+    let labels = [1., -1., 1., -1., 1., -1.];
+    let order = [5, 3, 1, 0, 2, 4];
+    let initial = LinearModel::new(vec![0.5, -0.5, 0., 1., 0.25], -0.5);
+    let mut dense_history = EpochHistory::default();
+    let mut sparse_history = EpochHistory::default();
+
+    let (theta, theta_0) = perceptron_with_options(
+        &dense,
+        &labels,
+        5,
+        options(&order, &initial, &mut dense_history),
+    )
+    .unwrap();
+    let (sparse_theta, sparse_theta_0) = perceptron_sparse_with_options(
+        &sparse,
+        &labels,
+        5,
+        options(&order, &initial, &mut sparse_history),
+    )
+    .unwrap();
+    assert_all_approx_eq(&sparse_theta, &theta);
+    assert_eq!(sparse_theta_0, theta_0);
+
+    let (theta, theta_0) = average_perceptron_with_options(
+        &dense,
+        &labels,
+        5,
+        options(&order, &initial, &mut dense_history),
+    )
+    .unwrap();
+    let (sparse_theta, sparse_theta_0) = average_perceptron_sparse_with_options(
+        &sparse,
+        &labels,
+        5,
+        options(&order, &initial, &mut sparse_history),
+    )
+    .unwrap();
+    assert_all_approx_eq(&sparse_theta, &theta);
+    assert!((sparse_theta_0 - theta_0).abs() < EPSILON);
+
+    let (theta, theta_0) = pegasos_with_options(
+        &dense,
+        &labels,
+        5,
+        0.2,
+        options(&order, &initial, &mut dense_history),
+    )
+    .unwrap();
+    let (sparse_theta, sparse_theta_0) = pegasos_sparse_with_options(
+        &sparse,
+        &labels,
+        5,
+        0.2,
+        options(&order, &initial, &mut sparse_history),
+    )
+    .unwrap();
+    assert_all_approx_eq(&sparse_theta, &theta);
+    assert!((sparse_theta_0 - theta_0).abs() < EPSILON);
+
+    assert_eq!(sparse_history.mistakes, dense_history.mistakes);
+    assert_eq!(sparse_history.theta_norms.len(), 15);
+}
+#[test]
+fn sparse_bow_features_match_dense() {
+    let texts = ["tasty tasty chips", "", "stale chips, not tasty"];
+    let dictionary = bag_of_words(&texts, None);
+
+    assert_eq!(
+        extract_bow_sparse_feature_vectors(&texts, &dictionary).to_dense(),
+        extract_bow_feature_vectors(&texts, &dictionary)
+    );
+}
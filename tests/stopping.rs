@@ -0,0 +1,141 @@
+mod common;
+
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::stopping::{EarlyStopping, StoppingRule};
+use automatic_review_analyzer::{perceptron, perceptron_early_stopping, perceptron_with_options};
+
+use common::separable_data;
+
+#[test]
+fn fixed_rule_runs_every_epoch() {
+    let (feature_matrix, labels) = separable_data();
+    let result =
+        perceptron_early_stopping(&feature_matrix, &labels, 7, StoppingRule::Fixed).unwrap();
+    assert_eq!(result.epochs_run, 7);
+    assert_eq!(result.best_epoch, 7);
+    assert_eq!(
+        result.thetas,
+        perceptron(&feature_matrix, &labels, 7).unwrap()
+    );
+}
+
+#[test]
+fn converged_rule_stops_after_a_pass_without_mistakes() {
+    let (feature_matrix, labels) = separable_data();
+    let result =
+        perceptron_early_stopping(&feature_matrix, &labels, 100, StoppingRule::Converged).unwrap();
+    assert!(result.epochs_run < 100);
+    assert_eq!(result.best_epoch, result.epochs_run);
+    assert_eq!(
+        result.thetas,
+        perceptron(&feature_matrix, &labels, result.epochs_run).unwrap()
+    );
+    assert_eq!(
+        result.thetas,
+        perceptron(&feature_matrix, &labels, 100).unwrap()
+    );
+}
+
+#[test]
+fn converged_rule_runs_every_epoch_on_inseparable_data() {
+    let feature_matrix = FeatureMatrix::from_rows(&[[1., 1.], [1., 1.]]).unwrap();
+    let labels = [1., -1.];
+    let result =
+        perceptron_early_stopping(&feature_matrix, &labels, 5, StoppingRule::Converged).unwrap();
+    assert_eq!(result.epochs_run, 5);
+}
+
+#[test]
+fn patience_rule_stops_when_validation_accuracy_stalls() {
+    let (feature_matrix, labels) = separable_data();
+    let val_feature_matrix = FeatureMatrix::from_rows(&[[1., 1.], [-2., -2.]]).unwrap();
+    let val_labels = [1., -1.];
+    let rule = StoppingRule::Patience {
+        feature_matrix: &val_feature_matrix,
+        labels: &val_labels,
+        patience: 2,
+    };
+    let result = perceptron_early_stopping(&feature_matrix, &labels, 100, rule).unwrap();
+    assert!(result.epochs_run < 100);
+    assert_eq!(result.best_epoch + 2, result.epochs_run);
+    assert_eq!(
+        result.thetas,
+        perceptron(&feature_matrix, &labels, result.best_epoch).unwrap()
+    );
+}
+
+#[test]
+fn patience_rule_returns_the_best_epoch() {
+    // Inseparable, so the perceptron keeps moving; only the parameters
+    // after the second epoch classify the validation point correctly.
+    let feature_matrix = FeatureMatrix::from_rows(&[
+        [2., 1.],
+        [1., 3.],
+        [-1., -2.],
+        [-3., -1.],
+        [1., -1.],
+        [-1., 1.],
+        [1., 1.],
+        [1., 1.],
+    ])
+    .unwrap();
+    let labels = [1., 1., -1., -1., -1., 1., 1., -1.];
+    let val_feature_matrix = FeatureMatrix::from_rows(&[[1., -0.4]]).unwrap();
+    let val_labels = [1.];
+    let rule = StoppingRule::Patience {
+        feature_matrix: &val_feature_matrix,
+        labels: &val_labels,
+        patience: 2,
+    };
+
+    let mut observer = EarlyStopping::new(rule);
+    let options = TrainOptions {
+        observer: Some(&mut observer),
+        ..Default::default()
+    };
+    let latest = perceptron_with_options(&feature_matrix, &labels, 100, options).unwrap();
+    assert_eq!(observer.epochs(), 4);
+    assert_eq!(observer.best_epoch(), Some(2));
+    assert_eq!(latest, perceptron(&feature_matrix, &labels, 4).unwrap());
+
+    let result = perceptron_early_stopping(&feature_matrix, &labels, 100, rule).unwrap();
+    assert_eq!(result.best_epoch, 2);
+    assert_eq!(result.epochs_run, 4);
+    assert_eq!(
+        result.thetas,
+        perceptron(&feature_matrix, &labels, 2).unwrap()
+    );
+    assert_ne!(result.thetas, latest);
+}
+
+#[test]
+fn patience_rule_is_validated() {
+    let (feature_matrix, labels) = separable_data();
+    let val_labels = [1.];
+
+    let val_feature_matrix = FeatureMatrix::from_rows(&[[1., 1.]]).unwrap();
+    let rule = StoppingRule::Patience {
+        feature_matrix: &val_feature_matrix,
+        labels: &val_labels,
+        patience: 0,
+    };
+    let result = perceptron_early_stopping(&feature_matrix, &labels, 10, rule);
+    assert!(matches!(
+        result,
+        Err(Error::InvalidParameter {
+            name: "patience",
+            ..
+        })
+    ));
+
+    let val_feature_matrix = FeatureMatrix::from_rows(&[[1., 1., 1.]]).unwrap();
+    let rule = StoppingRule::Patience {
+        feature_matrix: &val_feature_matrix,
+        labels: &val_labels,
+        patience: 1,
+    };
+    let result = perceptron_early_stopping(&feature_matrix, &labels, 10, rule);
+    assert!(matches!(result, Err(Error::DimensionMismatch { .. })));
+}
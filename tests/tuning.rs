@@ -0,0 +1,129 @@
+mod common;
+
+use automatic_review_analyzer::evaluation::classifier_accuracy;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::Algorithm;
+use automatic_review_analyzer::tuning::{tune, TuningGrid, TuningReport, TuningResult};
+use automatic_review_analyzer::{average_pegasos, DType};
+
+use common::separable_data;
+
+#[test]
+fn tune_grid_sizes() {
+    let (train, train_labels) = separable_data();
+    let val = FeatureMatrix::from_rows(&[[1., 1.], [-2., -2.]]).unwrap();
+    let val_labels = [1., -1.];
+    let grid = |algorithm, ts: &[usize], lambdas: &[DType]| TuningGrid {
+        algorithm,
+        ts: ts.to_vec(),
+        lambdas: lambdas.to_vec(),
+        tail: 0.5,
+    };
+    let run = |grid| tune(&grid, &train, &val, &train_labels, &val_labels).unwrap();
+
+    let report = run(grid(Algorithm::Perceptron, &[1, 5], &[0.1]));
+    assert_eq!(report.algorithm, Algorithm::Perceptron);
+    assert_eq!(report.results.len(), 2);
+    assert!(report
+        .results
+        .iter()
+        .all(|r| r.lambda.is_none() && r.tail.is_none()));
+
+    let report = run(grid(Algorithm::AveragePerceptron, &[1, 5, 10], &[]));
+    assert_eq!(report.algorithm, Algorithm::AveragePerceptron);
+    assert_eq!(report.results.len(), 3);
+
+    let report = run(grid(Algorithm::Pegasos, &[1, 5], &[0.01, 0.1, 1.]));
+    assert_eq!(report.results.len(), 6);
+    assert_eq!(
+        report
+            .results
+            .iter()
+            .map(|r| (r.t, r.lambda, r.tail))
+            .collect::<Vec<_>>(),
+        [
+            (1, Some(0.01), None),
+            (1, Some(0.1), None),
+            (1, Some(1.), None),
+            (5, Some(0.01), None),
+            (5, Some(0.1), None),
+            (5, Some(1.), None),
+        ]
+    );
+
+    let report = run(grid(Algorithm::AveragePegasos, &[1, 5], &[0.01, 0.1]));
+    assert_eq!(report.algorithm, Algorithm::AveragePegasos);
+    assert_eq!(report.results.len(), 4);
+    assert!(report.to_tsv().contains("\naverage_pegasos\t5\t0.1\t0.5\t"));
+    assert!(report.results.iter().all(|r| r.tail == Some(0.5)));
+}
+#[test]
+fn tune_matches_classifier_accuracy() {
+    let train = FeatureMatrix::from_rows(&[[2., 1.], [1., -3.], [-1., -2.], [-3., 1.]]).unwrap();
+    let val = FeatureMatrix::from_rows(&[[1., 1.], [-2., 2.], [0.5, -1.]]).unwrap();
+    let train_labels = [1., 1., -1., -1.];
+    let val_labels = [1., -1., -1.];
+    let grid = TuningGrid {
+        algorithm: Algorithm::AveragePegasos,
+        ts: vec![3],
+        lambdas: vec![0.1],
+        tail: 0.5,
+    };
+
+    let report = tune(&grid, &train, &val, &train_labels, &val_labels).unwrap();
+    let expected = classifier_accuracy(
+        |m, l| average_pegasos(m, l, 3, 0.1, 0.5),
+        &train,
+        &val,
+        &train_labels,
+        &val_labels,
+    )
+    .unwrap();
+    assert_eq!(
+        (
+            report.results[0].train_accuracy,
+            report.results[0].val_accuracy
+        ),
+        expected
+    );
+}
+#[test]
+fn best_prefers_first_highest_val_accuracy() {
+    let result = |t, val_accuracy| TuningResult {
+        t,
+        lambda: None,
+        tail: None,
+        train_accuracy: 1.,
+        val_accuracy,
+    };
+    let report = TuningReport {
+        algorithm: Algorithm::Perceptron,
+        results: vec![result(1, 0.5), result(5, 0.75), result(10, 0.75)],
+    };
+
+    assert_eq!(report.best().unwrap().t, 5);
+    assert!(TuningReport::<DType> {
+        algorithm: Algorithm::Perceptron,
+        results: vec![],
+    }
+    .best()
+    .is_none());
+}
+#[test]
+fn to_tsv_table() {
+    let report = TuningReport {
+        algorithm: Algorithm::Pegasos,
+        results: vec![TuningResult {
+            t: 10,
+            lambda: Some(0.01),
+            tail: None,
+            train_accuracy: 0.9,
+            val_accuracy: 0.8125,
+        }],
+    };
+
+    assert_eq!(
+        report.to_tsv(),
+        "algorithm\tt\tlambda\ttail\ttrain_accuracy\tval_accuracy\npegasos\t10\t0.01\t\t0.9000\t0.8125\n"
+    );
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use automatic_review_analyzer::error::Error;
+use automatic_review_analyzer::matrix::FeatureMatrix;
+use automatic_review_analyzer::model::{Algorithm, LinearModel};
+use automatic_review_analyzer::online::OnlineLearner;
+use automatic_review_analyzer::options::TrainOptions;
+use automatic_review_analyzer::order::get_order;
+use automatic_review_analyzer::{
+    average_pegasos, average_pegasos_with_options, average_perceptron,
+    average_perceptron_with_options, pegasos, pegasos_with_options, perceptron,
+    perceptron_with_options, DType,
+};
+
+use common::{feature_matrix, LABELS, LAMBDA, ROWS};
+
+fn initial() -> LinearModel {
+    LinearModel::new(vec![0.5, -1., 0.25], -0.5)
+}
+
+fn starting_from(initial: &LinearModel) -> TrainOptions<'_> {
+    TrainOptions {
+        initial: Some(initial),
+        ..Default::default()
+    }
+}
+
+/// Trains an online learner starting from `initial` on `t` passes through
+/// the data in the order visited by the training functions.
+fn online(algorithm: Algorithm, t: usize) -> LinearModel {
+    let order = get_order(ROWS.len());
+    let rows: Vec<_> = order.iter().map(|&i| ROWS[i]).collect();
+    let labels: Vec<_> = order.iter().map(|&i| LABELS[i]).collect();
+    let shuffled = FeatureMatrix::from_rows(&rows).unwrap();
+    let mut learner = OnlineLearner::from_model(algorithm, initial(), LAMBDA, 0).unwrap();
+    for _ in 0..t {
+        learner.partial_fit(&shuffled, &labels).unwrap();
+    }
+    learner.model()
+}
+
+fn assert_close(found: (Vec<DType>, DType), expected: LinearModel) {
+    for (found, expected) in found.0.iter().zip(&expected.theta) {
+        assert!((found - expected).abs() < 1e-5, "{found} != {expected}");
+    }
+    assert!((found.1 - expected.theta_0).abs() < 1e-5);
+}
+
+#[test]
+fn warm_start_from_zero_matches_cold_start() {
+    let feature_matrix = feature_matrix();
+    let zeros = LinearModel::zeros(3);
+    assert_eq!(
+        perceptron_with_options(&feature_matrix, &LABELS, 3, starting_from(&zeros)).unwrap(),
+        perceptron(&feature_matrix, &LABELS, 3).unwrap()
+    );
+    assert_eq!(
+        average_perceptron_with_options(&feature_matrix, &LABELS, 3, starting_from(&zeros))
+            .unwrap(),
+        average_perceptron(&feature_matrix, &LABELS, 3).unwrap()
+    );
+    assert_eq!(
+        pegasos_with_options(&feature_matrix, &LABELS, 3, LAMBDA, starting_from(&zeros)).unwrap(),
+        pegasos(&feature_matrix, &LABELS, 3, LAMBDA).unwrap()
+    );
+    assert_eq!(
+        average_pegasos_with_options(
+            &feature_matrix,
+            &LABELS,
+            3,
+            LAMBDA,
+            0.5,
+            starting_from(&zeros)
+        )
+        .unwrap(),
+        average_pegasos(&feature_matrix, &LABELS, 3, LAMBDA, 0.5).unwrap()
+    );
+}
+
+#[test]
+fn perceptron_warm_start_continues_training() {
+    let feature_matrix = feature_matrix();
+    let first = LinearModel::from(perceptron(&feature_matrix, &LABELS, 2).unwrap());
+    assert_eq!(
+        perceptron_with_options(&feature_matrix, &LABELS, 3, starting_from(&first)).unwrap(),
+        perceptron(&feature_matrix, &LABELS, 5).unwrap()
+    );
+}
+
+#[test]
+fn warm_start_from_given_parameters() {
+    let feature_matrix = feature_matrix();
+    assert_close(
+        perceptron_with_options(&feature_matrix, &LABELS, 2, starting_from(&initial())).unwrap(),
+        online(Algorithm::Perceptron, 2),
+    );
+    assert_close(
+        average_perceptron_with_options(&feature_matrix, &LABELS, 2, starting_from(&initial()))
+            .unwrap(),
+        online(Algorithm::AveragePerceptron, 2),
+    );
+    assert_close(
+        pegasos_with_options(
+            &feature_matrix,
+            &LABELS,
+            2,
+            LAMBDA,
+            starting_from(&initial()),
+        )
+        .unwrap(),
+        online(Algorithm::Pegasos, 2),
+    );
+    assert_close(
+        average_pegasos_with_options(
+            &feature_matrix,
+            &LABELS,
+            2,
+            LAMBDA,
+            1.,
+            starting_from(&initial()),
+        )
+        .unwrap(),
+        online(Algorithm::AveragePegasos, 2),
+    );
+}
+
+#[test]
+fn invalid_initial_model_fails() {
+    let feature_matrix = feature_matrix();
+    let short = LinearModel::zeros(2);
+    assert!(matches!(
+        perceptron_with_options(&feature_matrix, &LABELS, 1, starting_from(&short)),
+        Err(Error::DimensionMismatch {
+            what: "length of theta",
+            expected: 3,
+            found: 2,
+        })
+    ));
+    let infinite = LinearModel::new(vec![0., DType::INFINITY, 0.], 0.);
+    assert!(matches!(
+        pegasos_with_options(
+            &feature_matrix,
+            &LABELS,
+            1,
+            LAMBDA,
+            starting_from(&infinite)
+        ),
+        Err(Error::InvalidParameter {
+            name: "initial",
+            ..
+        })
+    ));
+}